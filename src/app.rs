@@ -62,13 +62,13 @@ pub struct App {
     schedule_flows: Vec<ScheduleHolder>,
 
     // Command buffers being received by the world
-    command_buffer: Receiver<Box<dyn FnMut(&mut World) -> ()>>,
+    command_buffer: Receiver<Box<dyn FnMut(&mut World) + Send>>,
 }
 
 
 impl App {
     pub fn new() -> Self {
-        let (sx, rx) = channel::<Box<dyn FnMut(&mut World) -> ()>>();
+        let (sx, rx) = channel::<Box<dyn FnMut(&mut World) + Send>>();
 
         App {
             world_container: UnsafeWorldContainer::new(sx),
@@ -132,6 +132,10 @@ impl App {
         for flow in &mut self.schedule_flows {
             flow.run_all(&self.world_container);
 
+            // Materialising the entities reserved by the systems, since
+            // the commands may operate on them
+            self.world_container.get_world_mut().flush_entities();
+
             // Flushing and executing the command buffer
             let mut result = self.command_buffer.try_recv();
 
@@ -153,6 +157,9 @@ impl App {
     /// A component must be registered in a world before adding
     /// the component to any entity in the world. 
     /// 
+    /// WARNING: Registering a component whose required components
+    ///     form a cycle will result in a panic
+    ///
     pub fn register_component<C: Component + 'static>(&mut self) {
         self.world_container
             .get_world_mut()
@@ -0,0 +1,581 @@
+use std::any::TypeId;
+
+use hashbrown::HashMap;
+
+use super::{Archetype, ArchetypeId, EntityLocation, EMPTY_ARCHETYPE_ID};
+use crate::{
+    component::{
+        bundle::BundleComponent,
+        column::{Column, ComponentColumn},
+        tick::{ComponentTicks, Tick},
+        Component,
+    },
+    entity::Entity,
+};
+
+///
+/// ### Description
+///
+/// Structure responsible for storing all the [Archetype] tables of
+/// a [`world`](crate::world::World) along with the location of each
+/// entity inside of these tables.
+///
+/// Adding or removing a component from an entity moves the entity
+/// (along with all its components) from its current archetype into the
+/// archetype matching its new set of components. The target archetype
+/// is created the first time a set of components is encountered.
+///
+pub(crate) struct ArchetypeManager {
+    /// Storage for archetypes, indexed by [ArchetypeId]
+    archetypes: Vec<Archetype>,
+
+    /// Sorted component set to archetype mapping
+    archetype_index: HashMap<Vec<TypeId>, ArchetypeId>,
+
+    /// Location of every entity alive in the world
+    entity_locations: HashMap<Entity, EntityLocation>,
+}
+
+impl ArchetypeManager {
+    pub fn new() -> Self {
+        let mut archetype_index = HashMap::new();
+        archetype_index.insert(vec![], EMPTY_ARCHETYPE_ID);
+
+        Self {
+            archetypes: vec![Archetype::new(EMPTY_ARCHETYPE_ID, vec![], HashMap::new())],
+            archetype_index,
+            entity_locations: HashMap::new(),
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns an iterator over all the archetypes which store every
+    /// one of the given component types.
+    ///
+    /// Empty archetypes are skipped since they have nothing to offer
+    /// to a query.
+    pub fn get_archetypes_with_components<'a, 'b>(
+        &'a self,
+        component_ids: &'b [TypeId],
+    ) -> impl Iterator<Item = &'a Archetype> + 'b
+    where
+        'a: 'b,
+    {
+        self.archetypes
+            .iter()
+            .filter(move |archetype| !archetype.is_empty() && archetype.contains_all(component_ids))
+    }
+
+    /// Returns every archetype, indexed by [ArchetypeId]
+    pub fn archetypes(&self) -> &[Archetype] {
+        &self.archetypes
+    }
+
+    pub fn get_archetype(&self, archetype_id: ArchetypeId) -> &Archetype {
+        &self.archetypes[archetype_id]
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Places a newly created entity in the empty archetype
+    pub fn insert_entity(&mut self, entity_id: Entity) {
+        let row = self.archetypes[EMPTY_ARCHETYPE_ID].push_entity(entity_id);
+        self.entity_locations.insert(
+            entity_id,
+            EntityLocation {
+                archetype_id: EMPTY_ARCHETYPE_ID,
+                row,
+            },
+        );
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Removes the entity from its archetype and drops all the
+    /// components attached to it.
+    ///
+    /// ### Return Value
+    /// [`false`](bool) if the entity was not present in the tables,
+    /// [`true`](bool) otherwise
+    pub fn remove_entity(&mut self, entity_id: Entity) -> bool {
+        let location = match self.entity_locations.remove(&entity_id) {
+            Some(location) => location,
+            None => return false,
+        };
+
+        let moved_entity = self.archetypes[location.archetype_id].swap_remove(location.row);
+        self.update_moved_entity(moved_entity, location);
+        true
+    }
+
+    /// Returns the sorted [TypeId]s of the dense components attached to the entity
+    pub fn get_component_ids(&self, entity_id: Entity) -> Option<&[TypeId]> {
+        let location = self.entity_locations.get(&entity_id)?;
+        Some(self.archetypes[location.archetype_id].component_ids())
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns pointers to the component of type [`C`] attached to the
+    /// entity and its change ticks, or None if the entity does not have
+    /// the component.
+    ///
+    /// See [Column::get_ptr] for the access rules of the pointers
+    pub fn get_component_ptrs<C: Component + 'static>(
+        &self,
+        entity_id: Entity,
+    ) -> Option<(*mut C, *mut ComponentTicks)> {
+        let location = self.entity_locations.get(&entity_id)?;
+        let column = self.archetypes[location.archetype_id].get_column::<C>()?;
+        Some((column.get_ptr(location.row), column.get_ticks_ptr(location.row)))
+    }
+
+    pub fn has_component(&self, entity_id: Entity, component_id: TypeId) -> bool {
+        match self.entity_locations.get(&entity_id) {
+            Some(location) => self.archetypes[location.archetype_id].contains(component_id),
+            None => false,
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Attaches the component to the entity by moving the entity into
+    /// the archetype which contains the component type along with the
+    /// current components of the entity.
+    ///
+    /// WARNING: Adding a component type which is already attached to the
+    ///     entity will result in a panic
+    pub fn add_component<C: Component + 'static>(
+        &mut self,
+        entity_id: Entity,
+        component: C,
+        tick: Tick,
+    ) {
+        let location = match self.entity_locations.get(&entity_id) {
+            Some(location) => *location,
+            None => {
+                log::warn!("Component addition failed: Entity [{:?}] does not exist", entity_id);
+                return;
+            }
+        };
+
+        assert!(
+            !self.archetypes[location.archetype_id].contains(TypeId::of::<C>()),
+            "Component addition to Entity [{:?}] failed: Duplicate components are not allowed in entities.",
+            entity_id
+        );
+
+        let target_id = self.get_archetype_after_addition::<C>(location.archetype_id);
+        let new_row = self.move_entity(location, target_id);
+
+        self.archetypes[target_id]
+            .get_column_mut::<C>()
+            .unwrap()
+            .push(component, tick);
+
+        self.entity_locations.insert(
+            entity_id,
+            EntityLocation {
+                archetype_id: target_id,
+                row: new_row,
+            },
+        );
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Replaces the component of type [`C`] attached to the entity
+    /// without moving the entity across archetypes
+    ///
+    /// WARNING: Replacing a component type which is not attached to the
+    ///     entity will result in a panic
+    pub fn replace_component<C: Component + 'static>(
+        &mut self,
+        entity_id: Entity,
+        component: C,
+        tick: Tick,
+    ) {
+        let location = match self.entity_locations.get(&entity_id) {
+            Some(location) => *location,
+            None => {
+                log::warn!("Component replacement failed: Entity [{:?}] does not exist", entity_id);
+                return;
+            }
+        };
+
+        self.archetypes[location.archetype_id]
+            .get_column_mut::<C>()
+            .unwrap_or_else(|| {
+                panic!("Component replacement failed: Entity [{:?}] does not have the component", entity_id)
+            })
+            .replace(location.row, component, tick);
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Detaches and drops the component of the given type from the entity
+    /// by moving the entity into the archetype without the component type
+    ///
+    /// ### Return Value
+    /// [`false`](bool) if the component was not attached to the entity,
+    /// [`true`](bool) otherwise
+    pub fn remove_component(&mut self, entity_id: Entity, component_id: TypeId) -> bool {
+        let location = match self.entity_locations.get(&entity_id) {
+            Some(location) => *location,
+            None => return false,
+        };
+
+        if !self.archetypes[location.archetype_id].contains(component_id) {
+            return false;
+        }
+
+        let target_id = self.get_archetype_after_removal(location.archetype_id, component_id);
+        let new_row = self.move_entity(location, target_id);
+
+        self.entity_locations.insert(
+            entity_id,
+            EntityLocation {
+                archetype_id: target_id,
+                row: new_row,
+            },
+        );
+        true
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Moves the entity into the archetype which contains the given
+    /// component types along with the current components of the entity,
+    /// and returns the archetype.
+    ///
+    /// The components themselves must be pushed into the returned
+    /// archetype by the caller, see [`BundleWriter`](crate::component::bundle::BundleWriter)
+    ///
+    /// WARNING: Adding a component type which is already attached to the
+    ///     entity will result in a panic
+    pub fn add_components(
+        &mut self,
+        entity_id: Entity,
+        components: &[&BundleComponent],
+    ) -> Option<&mut Archetype> {
+        let location = match self.entity_locations.get(&entity_id) {
+            Some(location) => *location,
+            None => {
+                log::warn!("Component addition failed: Entity [{:?}] does not exist", entity_id);
+                return None;
+            }
+        };
+
+        for component in components {
+            assert!(
+                !self.archetypes[location.archetype_id].contains(component.type_id),
+                "Component addition to Entity [{:?}] failed: Duplicate components are not allowed in entities.",
+                entity_id
+            );
+        }
+
+        if components.is_empty() {
+            return Some(&mut self.archetypes[location.archetype_id]);
+        }
+
+        let source = &self.archetypes[location.archetype_id];
+        let mut component_ids = source.component_ids().to_vec();
+        component_ids.extend(components.iter().map(|component| component.type_id));
+        component_ids.sort();
+
+        let target_id = match self.archetype_index.get(&component_ids) {
+            Some(target_id) => *target_id,
+            None => {
+                let mut columns = source.create_empty_columns();
+                for component in components {
+                    columns.insert(component.type_id, (component.new_column)());
+                }
+                self.create_archetype(component_ids, columns)
+            }
+        };
+
+        let new_row = self.move_entity(location, target_id);
+        self.entity_locations.insert(
+            entity_id,
+            EntityLocation {
+                archetype_id: target_id,
+                row: new_row,
+            },
+        );
+        Some(&mut self.archetypes[target_id])
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Detaches and drops the components of the given types from the
+    /// entity, moving the entity into its new archetype only once.
+    ///
+    /// Component types which are not attached to the entity are ignored
+    ///
+    /// ### Return Value
+    /// [`false`](bool) if the entity does not exist, [`true`](bool) otherwise
+    pub fn remove_components(&mut self, entity_id: Entity, component_ids: &[TypeId]) -> bool {
+        let location = match self.entity_locations.get(&entity_id) {
+            Some(location) => *location,
+            None => return false,
+        };
+
+        let source = &self.archetypes[location.archetype_id];
+        let remaining_ids: Vec<TypeId> = source
+            .component_ids()
+            .iter()
+            .filter(|cid| !component_ids.contains(cid))
+            .copied()
+            .collect();
+
+        if remaining_ids.len() == source.component_ids().len() {
+            return true;
+        }
+
+        let target_id = match self.archetype_index.get(&remaining_ids) {
+            Some(target_id) => *target_id,
+            None => {
+                let mut columns = source.create_empty_columns();
+                columns.retain(|cid, _| !component_ids.contains(cid));
+                self.create_archetype(remaining_ids, columns)
+            }
+        };
+
+        let new_row = self.move_entity(location, target_id);
+        self.entity_locations.insert(
+            entity_id,
+            EntityLocation {
+                archetype_id: target_id,
+                row: new_row,
+            },
+        );
+        true
+    }
+}
+
+/// Private methods for [ArchetypeManager]
+impl ArchetypeManager {
+    ///
+    /// Finds (or creates) the archetype reached by adding a component
+    /// of type [`C`] into the source archetype
+    fn get_archetype_after_addition<C: Component + 'static>(
+        &mut self,
+        source_id: ArchetypeId,
+    ) -> ArchetypeId {
+        let component_id = TypeId::of::<C>();
+        if let Some(target_id) = self.archetypes[source_id].get_add_edge(component_id) {
+            return target_id;
+        }
+
+        let source = &self.archetypes[source_id];
+        let mut component_ids = source.component_ids().to_vec();
+        component_ids.push(component_id);
+        component_ids.sort();
+
+        let target_id = match self.archetype_index.get(&component_ids) {
+            Some(target_id) => *target_id,
+            None => {
+                let mut columns = source.create_empty_columns();
+                columns.insert(component_id, Box::new(Column::<C>::new()));
+                self.create_archetype(component_ids, columns)
+            }
+        };
+
+        self.archetypes[source_id].set_add_edge(component_id, target_id);
+        self.archetypes[target_id].set_remove_edge(component_id, source_id);
+        target_id
+    }
+
+    ///
+    /// Finds (or creates) the archetype reached by removing the given
+    /// component type from the source archetype
+    fn get_archetype_after_removal(
+        &mut self,
+        source_id: ArchetypeId,
+        component_id: TypeId,
+    ) -> ArchetypeId {
+        if let Some(target_id) = self.archetypes[source_id].get_remove_edge(component_id) {
+            return target_id;
+        }
+
+        let source = &self.archetypes[source_id];
+        let component_ids: Vec<TypeId> = source
+            .component_ids()
+            .iter()
+            .filter(|cid| **cid != component_id)
+            .copied()
+            .collect();
+
+        let target_id = match self.archetype_index.get(&component_ids) {
+            Some(target_id) => *target_id,
+            None => {
+                let mut columns = source.create_empty_columns();
+                columns.remove(&component_id);
+                self.create_archetype(component_ids, columns)
+            }
+        };
+
+        self.archetypes[source_id].set_remove_edge(component_id, target_id);
+        self.archetypes[target_id].set_add_edge(component_id, source_id);
+        target_id
+    }
+
+    fn create_archetype(
+        &mut self,
+        component_ids: Vec<TypeId>,
+        columns: HashMap<TypeId, Box<dyn ComponentColumn>>,
+    ) -> ArchetypeId {
+        let archetype_id = self.archetypes.len();
+        self.archetype_index.insert(component_ids.clone(), archetype_id);
+        self.archetypes
+            .push(Archetype::new(archetype_id, component_ids, columns));
+        archetype_id
+    }
+
+    ///
+    /// Moves the entity present at the given location into the target
+    /// archetype and returns the row of the entity in the target table.
+    ///
+    /// The location of the entity itself is not updated here, and must
+    /// be updated by the caller
+    fn move_entity(&mut self, location: EntityLocation, target_id: ArchetypeId) -> usize {
+        let (source, target) = Self::get_archetype_pair_mut(
+            &mut self.archetypes,
+            location.archetype_id,
+            target_id,
+        );
+
+        let (new_row, moved_entity) = source.move_entity(location.row, target);
+        self.update_moved_entity(moved_entity, location);
+        new_row
+    }
+
+    /// Updates the location of an entity which was swapped into
+    /// a vacated row of a table
+    fn update_moved_entity(&mut self, moved_entity: Option<Entity>, vacated: EntityLocation) {
+        if let Some(moved_entity) = moved_entity {
+            self.entity_locations.insert(moved_entity, vacated);
+        }
+    }
+
+    fn get_archetype_pair_mut(
+        archetypes: &mut [Archetype],
+        first: ArchetypeId,
+        second: ArchetypeId,
+    ) -> (&mut Archetype, &mut Archetype) {
+        assert_ne!(first, second, "Cannot borrow the same archetype twice");
+
+        if first < second {
+            let (left, right) = archetypes.split_at_mut(second);
+            (&mut left[first], &mut right[0])
+        } else {
+            let (left, right) = archetypes.split_at_mut(first);
+            (&mut right[0], &mut left[second])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::ecs_base::ECSBase;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Position(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Velocity(u32);
+
+    const TICK: Tick = Tick::new(1);
+
+    fn spawn(manager: &mut ArchetypeManager, index: u32) -> Entity {
+        let entity_id = Entity::from_bits((index as u64) << 32 | NonZeroU32::MIN.get() as u64);
+        manager.insert_entity(entity_id);
+        entity_id
+    }
+
+    fn get<C: Component + 'static>(manager: &ArchetypeManager, entity_id: Entity) -> Option<&C> {
+        // @SAFETY: The manager is borrowed for as long as the reference is used
+        manager
+            .get_component_ptrs::<C>(entity_id)
+            .map(|(ptr, _)| unsafe { &*ptr })
+    }
+
+    #[test]
+    fn entities_move_between_archetype_tables() {
+        let mut manager = ArchetypeManager::new();
+        let first = spawn(&mut manager, 0);
+        let second = spawn(&mut manager, 1);
+
+        manager.add_component(first, Position(1), TICK);
+        manager.add_component(second, Position(2), TICK);
+        manager.add_component(first, Velocity(10), TICK);
+
+        assert_eq!(manager.get_component_ids(first).unwrap().len(), 2);
+        assert_eq!(get::<Position>(&manager, first), Some(&Position(1)));
+        assert_eq!(get::<Velocity>(&manager, first), Some(&Velocity(10)));
+        assert_eq!(get::<Position>(&manager, second), Some(&Position(2)));
+        assert_eq!(get::<Velocity>(&manager, second), None);
+
+        // Removing the component moves the entity back into the table of its remaining components
+        assert!(manager.remove_component(first, TypeId::of::<Velocity>()));
+        assert!(!manager.remove_component(first, TypeId::of::<Velocity>()));
+        assert_eq!(manager.get_component_ids(first), manager.get_component_ids(second));
+        assert_eq!(get::<Position>(&manager, first), Some(&Position(1)));
+    }
+
+    #[test]
+    fn swap_removed_entities_keep_their_components() {
+        let mut manager = ArchetypeManager::new();
+        let entities: Vec<Entity> = (0..3).map(|index| spawn(&mut manager, index)).collect();
+        for (value, entity_id) in entities.iter().enumerate() {
+            manager.add_component(*entity_id, Position(value as u32), TICK);
+        }
+
+        // The last entity of the table takes the place of the removed one
+        assert!(manager.remove_entity(entities[0]));
+        assert!(!manager.remove_entity(entities[0]));
+        assert_eq!(get::<Position>(&manager, entities[0]), None);
+        assert_eq!(get::<Position>(&manager, entities[1]), Some(&Position(1)));
+        assert_eq!(get::<Position>(&manager, entities[2]), Some(&Position(2)));
+    }
+
+    #[test]
+    fn component_sets_share_one_archetype() {
+        let mut manager = ArchetypeManager::new();
+        let first = spawn(&mut manager, 0);
+        let second = spawn(&mut manager, 1);
+
+        manager.add_component(first, Position(1), TICK);
+        manager.add_component(first, Velocity(1), TICK);
+        manager.add_component(second, Velocity(2), TICK);
+        manager.add_component(second, Position(2), TICK);
+
+        let archetypes_count = manager.archetypes().len();
+        let matched: Vec<ArchetypeId> = manager
+            .get_archetypes_with_components(&[TypeId::of::<Position>(), TypeId::of::<Velocity>()])
+            .map(|archetype| archetype.id())
+            .collect();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(manager.get_archetype(matched[0]).entities(), [first, second]);
+
+        // Reaching a known component set does not create a new archetype
+        manager.remove_component(first, TypeId::of::<Velocity>());
+        manager.add_component(first, Velocity(1), TICK);
+        assert_eq!(manager.archetypes().len(), archetypes_count);
+    }
+}
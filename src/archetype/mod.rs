@@ -0,0 +1,245 @@
+pub(crate) mod archetype_manager;
+
+use std::any::TypeId;
+
+use hashbrown::HashMap;
+
+use crate::{
+    component::{
+        column::{Column, ComponentColumn},
+        Component,
+    },
+    entity::Entity,
+};
+
+/// Index of an [Archetype] inside of the
+/// [`archetype manager`](archetype_manager::ArchetypeManager)
+pub type ArchetypeId = usize;
+
+/// The id of the archetype storing entities without any components
+pub const EMPTY_ARCHETYPE_ID: ArchetypeId = 0;
+
+///
+/// ### Description
+///
+/// Location of an [Entity] inside of the archetype tables.
+///
+/// The components of the entity can be found at the [`row`](EntityLocation::row)
+/// index in each column of the archetype identified by
+/// [`archetype_id`](EntityLocation::archetype_id)
+///
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EntityLocation {
+    pub(crate) archetype_id: ArchetypeId,
+    pub(crate) row: usize,
+}
+
+///
+/// ### Description
+///
+/// An [Archetype] is a table storing all entities which have the exact
+/// same set of components attached to them.
+///
+/// Each component type in the set is stored in its own contiguous
+/// [`column`](ComponentColumn), and the components of a single entity
+/// are present at the same row in every column of the table. The
+/// entities themselves are stored in the same row order in the
+/// [`entities`](Archetype::entities) vector.
+///
+/// This allows queries to iterate over all matching archetypes directly
+/// instead of finding the entities which have the requested components.
+///
+pub struct Archetype {
+    id: ArchetypeId,
+
+    /// Sorted [TypeId]s of the components stored in this archetype.
+    /// The sorted order acts as the identity of the archetype.
+    component_ids: Vec<TypeId>,
+
+    /// Entities stored in the archetype, in the same order
+    /// as the rows of the columns
+    entities: Vec<Entity>,
+
+    /// Component storage of the archetype, one column per component type
+    columns: HashMap<TypeId, Box<dyn ComponentColumn>>,
+
+    /// Archetypes reached by adding a component type to this archetype.
+    /// Cached to avoid looking up the target component set on every addition
+    add_edges: HashMap<TypeId, ArchetypeId>,
+
+    /// Archetypes reached by removing a component type from this archetype.
+    remove_edges: HashMap<TypeId, ArchetypeId>,
+}
+
+impl Archetype {
+    pub(crate) fn new(
+        id: ArchetypeId,
+        component_ids: Vec<TypeId>,
+        columns: HashMap<TypeId, Box<dyn ComponentColumn>>,
+    ) -> Self {
+        Self {
+            id,
+            component_ids,
+            entities: vec![],
+            columns,
+            add_edges: HashMap::new(),
+            remove_edges: HashMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> ArchetypeId {
+        self.id
+    }
+
+    /// Returns the sorted [TypeId]s of the components stored in the archetype
+    pub fn component_ids(&self) -> &[TypeId] {
+        &self.component_ids
+    }
+
+    /// Returns the entities stored in the archetype in row order
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn contains(&self, component_id: TypeId) -> bool {
+        self.columns.contains_key(&component_id)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns true if the archetype stores every one of the
+    /// given component types, false otherwise
+    pub fn contains_all(&self, component_ids: &[TypeId]) -> bool {
+        component_ids.iter().all(|cid| self.columns.contains_key(cid))
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the column of the archetype storing components of type [`C`]
+    ///
+    /// If the component type is not a part of the archetype, None is returned
+    pub(crate) fn get_column<C: Component + 'static>(&self) -> Option<&Column<C>> {
+        self.columns
+            .get(&TypeId::of::<C>())
+            .and_then(|column| column.as_any().downcast_ref::<Column<C>>())
+    }
+
+    pub(crate) fn get_column_mut<C: Component + 'static>(&mut self) -> Option<&mut Column<C>> {
+        self.columns
+            .get_mut(&TypeId::of::<C>())
+            .and_then(|column| column.as_any_mut().downcast_mut::<Column<C>>())
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Creates empty columns for all the component types of the archetype.
+    ///
+    /// Used to create the columns of a new archetype which shares
+    /// a subset of its components with this archetype
+    pub(crate) fn create_empty_columns(&self) -> HashMap<TypeId, Box<dyn ComponentColumn>> {
+        self.columns
+            .iter()
+            .map(|(cid, column)| (*cid, column.new_empty()))
+            .collect()
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Pushes the entity at the end of the entity list of the archetype
+    /// and returns the row assigned to the entity.
+    ///
+    /// WARNING: The caller is responsible for pushing the components of
+    ///     the entity into every column of the archetype.
+    pub(crate) fn push_entity(&mut self, entity: Entity) -> usize {
+        self.entities.push(entity);
+        self.entities.len() - 1
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Removes the entity at the given row along with all of its components
+    ///
+    /// ### Return Value
+    /// The entity which was moved into the removed row to fill the gap,
+    /// if the removed row was not the last row of the table
+    pub(crate) fn swap_remove(&mut self, row: usize) -> Option<Entity> {
+        for column in self.columns.values_mut() {
+            column.swap_remove(row);
+        }
+
+        self.swap_remove_entity(row)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Moves the components of the entity at the given row into
+    /// the `destination` archetype.
+    ///
+    /// Components whose type is not a part of the destination archetype
+    /// are dropped. Components required by the destination archetype
+    /// which are not a part of this archetype must be pushed by the
+    /// caller.
+    ///
+    /// ### Return Value
+    /// A tuple containing the row of the entity in the destination table
+    /// and the entity which was moved into the vacated row of this table
+    pub(crate) fn move_entity(
+        &mut self,
+        row: usize,
+        destination: &mut Archetype,
+    ) -> (usize, Option<Entity>) {
+        for (cid, column) in self.columns.iter_mut() {
+            match destination.columns.get_mut(cid) {
+                Some(destination_column) => column.move_row(row, destination_column.as_mut()),
+                None => column.swap_remove(row),
+            }
+        }
+
+        let new_row = destination.push_entity(self.entities[row]);
+        (new_row, self.swap_remove_entity(row))
+    }
+
+    pub(crate) fn get_add_edge(&self, component_id: TypeId) -> Option<ArchetypeId> {
+        self.add_edges.get(&component_id).copied()
+    }
+
+    pub(crate) fn get_remove_edge(&self, component_id: TypeId) -> Option<ArchetypeId> {
+        self.remove_edges.get(&component_id).copied()
+    }
+
+    pub(crate) fn set_add_edge(&mut self, component_id: TypeId, archetype_id: ArchetypeId) {
+        self.add_edges.insert(component_id, archetype_id);
+    }
+
+    pub(crate) fn set_remove_edge(&mut self, component_id: TypeId, archetype_id: ArchetypeId) {
+        self.remove_edges.insert(component_id, archetype_id);
+    }
+}
+
+/// Private methods for [Archetype]
+impl Archetype {
+    fn swap_remove_entity(&mut self, row: usize) -> Option<Entity> {
+        self.entities.swap_remove(row);
+
+        // The last entity of the table now occupies the removed row
+        if row < self.entities.len() {
+            Some(self.entities[row])
+        } else {
+            None
+        }
+    }
+}
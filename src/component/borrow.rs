@@ -0,0 +1,271 @@
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicIsize, Ordering},
+        Arc, MutexGuard,
+    },
+};
+
+/// Flag value of a column which is borrowed mutably
+const MUT_BORROWED: isize = -1;
+
+///
+/// ### Description
+///
+/// Borrow state of a component storage (an archetype
+/// [`column`](super::column::Column) or a [`sparse set`](super::sparse_set::SparseSet)).
+///
+/// The [`scheduler`](crate::schedule::graph::DependencyGraph) already
+/// guarantees that systems with conflicting accesses never run in the same
+/// layer (see [`SystemMetadata::is_resource_clashing`](crate::system::dependency::SystemMetadata::is_resource_clashing)).
+/// Hence, instead of locking every component, we only track the borrows
+/// of the storage as a whole, which lets the components themselves be
+/// accessed through plain references.
+///
+/// A positive flag value is the number of active shared borrows, while
+/// [`MUT_BORROWED`] marks an exclusive borrow.
+///
+pub struct BorrowFlag {
+    flag: AtomicIsize,
+}
+
+impl BorrowFlag {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self {
+            flag: AtomicIsize::new(0),
+        })
+    }
+}
+
+///
+/// ### Description
+///
+/// A borrow acquired on a component storage. The borrow is released
+/// when this object is dropped.
+///
+pub struct ColumnBorrow {
+    flag: Arc<BorrowFlag>,
+    mutable: bool,
+}
+
+impl ColumnBorrow {
+    ///
+    /// ### Description
+    ///
+    /// Acquires a shared borrow on the storage.
+    ///
+    /// Fails if the storage is borrowed mutably
+    pub(crate) fn try_borrow(flag: &Arc<BorrowFlag>) -> Option<Self> {
+        let mut current = flag.flag.load(Ordering::Acquire);
+        loop {
+            if current == MUT_BORROWED {
+                return None;
+            }
+
+            match flag.flag.compare_exchange_weak(
+                current,
+                current + 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    return Some(Self {
+                        flag: flag.clone(),
+                        mutable: false,
+                    })
+                }
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Acquires an exclusive borrow on the storage.
+    ///
+    /// Fails if the storage is borrowed in any way
+    pub(crate) fn try_borrow_mut(flag: &Arc<BorrowFlag>) -> Option<Self> {
+        flag.flag
+            .compare_exchange(0, MUT_BORROWED, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Self {
+                flag: flag.clone(),
+                mutable: true,
+            })
+    }
+
+}
+
+impl Drop for ColumnBorrow {
+    fn drop(&mut self) {
+        if self.mutable {
+            self.flag.flag.store(0, Ordering::Release);
+        } else {
+            self.flag.flag.fetch_sub(1, Ordering::Release);
+        }
+    }
+}
+
+///
+/// ### Description
+///
+/// Collection of the [ColumnBorrow]s acquired by a single
+/// [`system param`](crate::system::param::SystemParam).
+///
+/// A parameter may reach the same storage multiple times (a sparse
+/// set is shared by every archetype a query iterates over), hence
+/// borrows of a storage which is already borrowed in the same mode by
+/// this collection are not acquired again. Requesting a storage in the
+/// other mode than the one it is held in fails, since it would hand out
+/// shared and exclusive references into the same storage. Only the
+/// [`filter borrows`](ComponentBorrows::try_borrow_filter) are satisfied
+/// by an exclusive borrow held by the collection.
+///
+/// All the borrows are released when the collection is dropped.
+///
+/// The collection can not be sent to another thread, which keeps the
+/// parameters owning it out of the [`commands`](crate::system::param::CommandBufferWriter::add_command)
+/// of the system: the borrows must be released by the system holding them.
+///
+pub struct ComponentBorrows {
+    borrows: Vec<ColumnBorrow>,
+
+    /// Opts out of [Send], while remaining [Sync]
+    _not_send: PhantomData<MutexGuard<'static, ()>>,
+}
+
+impl ComponentBorrows {
+    pub(crate) fn new() -> Self {
+        Self {
+            borrows: vec![],
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Acquires a shared borrow on the storage, see [ColumnBorrow::try_borrow]
+    pub(crate) fn try_borrow(&mut self, flag: &Arc<BorrowFlag>) -> bool {
+        match self.get_borrow_mode(flag) {
+            Some(mutable) => !mutable,
+            None => self.push(ColumnBorrow::try_borrow(flag)),
+        }
+    }
+
+    /// Acquires an exclusive borrow on the storage, see [ColumnBorrow::try_borrow_mut]
+    pub(crate) fn try_borrow_mut(&mut self, flag: &Arc<BorrowFlag>) -> bool {
+        match self.get_borrow_mode(flag) {
+            Some(mutable) => mutable,
+            None => self.push(ColumnBorrow::try_borrow_mut(flag)),
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Acquires a shared borrow on the storage read by a
+    /// [`query filter`](crate::system::param::QueryFilter).
+    ///
+    /// Filters only read the storage while the entities of the query are
+    /// matched, before any handle is handed out, hence the borrow is also
+    /// satisfied by an exclusive borrow held by the collection
+    pub(crate) fn try_borrow_filter(&mut self, flag: &Arc<BorrowFlag>) -> bool {
+        match self.get_borrow_mode(flag) {
+            Some(_) => true,
+            None => self.push(ColumnBorrow::try_borrow(flag)),
+        }
+    }
+}
+
+/// Private methods for [ComponentBorrows]
+impl ComponentBorrows {
+    /// Returns the mode the storage is borrowed in by the collection, if any
+    fn get_borrow_mode(&self, flag: &Arc<BorrowFlag>) -> Option<bool> {
+        self.borrows
+            .iter()
+            .find(|borrow| Arc::ptr_eq(&borrow.flag, flag))
+            .map(|borrow| borrow.mutable)
+    }
+
+    fn push(&mut self, borrow: Option<ColumnBorrow>) -> bool {
+        match borrow {
+            Some(borrow) => {
+                self.borrows.push(borrow);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflicting_borrows_fail() {
+        let flag = BorrowFlag::new();
+        let mut reader = ComponentBorrows::new();
+        let mut other_reader = ComponentBorrows::new();
+        let mut writer = ComponentBorrows::new();
+
+        assert!(reader.try_borrow(&flag));
+        assert!(other_reader.try_borrow(&flag));
+        assert!(!writer.try_borrow_mut(&flag));
+
+        drop(reader);
+        drop(other_reader);
+        assert!(writer.try_borrow_mut(&flag));
+        assert!(!ComponentBorrows::new().try_borrow(&flag));
+
+        drop(writer);
+        assert_eq!(flag.flag.load(Ordering::Acquire), 0);
+    }
+
+    #[test]
+    fn repeated_borrows_are_acquired_once() {
+        let flag = BorrowFlag::new();
+        let mut borrows = ComponentBorrows::new();
+
+        assert!(borrows.try_borrow(&flag));
+        assert!(borrows.try_borrow(&flag));
+        assert_eq!(flag.flag.load(Ordering::Acquire), 1);
+
+        let mut writer = ComponentBorrows::new();
+        let other_flag = BorrowFlag::new();
+        assert!(writer.try_borrow_mut(&other_flag));
+        assert!(writer.try_borrow_mut(&other_flag));
+        assert_eq!(other_flag.flag.load(Ordering::Acquire), MUT_BORROWED);
+    }
+
+    #[test]
+    fn conflicting_modes_of_the_same_collection_fail() {
+        let flag = BorrowFlag::new();
+        let mut reader = ComponentBorrows::new();
+        assert!(reader.try_borrow(&flag));
+        assert!(!reader.try_borrow_mut(&flag));
+        assert_eq!(flag.flag.load(Ordering::Acquire), 1);
+
+        let other_flag = BorrowFlag::new();
+        let mut writer = ComponentBorrows::new();
+        assert!(writer.try_borrow_mut(&other_flag));
+        assert!(!writer.try_borrow(&other_flag));
+        assert_eq!(other_flag.flag.load(Ordering::Acquire), MUT_BORROWED);
+    }
+
+    #[test]
+    fn filter_borrows_are_satisfied_by_any_mode() {
+        let flag = BorrowFlag::new();
+        let mut writer = ComponentBorrows::new();
+        assert!(writer.try_borrow_mut(&flag));
+        assert!(writer.try_borrow_filter(&flag));
+        assert_eq!(flag.flag.load(Ordering::Acquire), MUT_BORROWED);
+
+        drop(writer);
+        let mut reader = ComponentBorrows::new();
+        assert!(reader.try_borrow_filter(&flag));
+        assert!(!ComponentBorrows::new().try_borrow_mut(&flag));
+
+        drop(reader);
+        assert_eq!(flag.flag.load(Ordering::Acquire), 0);
+    }
+}
@@ -0,0 +1,297 @@
+use std::any::TypeId;
+
+use hashbrown::HashMap;
+
+use super::{
+    column::{Column, ComponentColumn},
+    sparse_set::{ComponentSparseSet, SparseSet},
+    tick::Tick,
+    Component, StorageType,
+};
+use crate::{archetype::Archetype, entity::Entity};
+
+///
+/// ### Description
+///
+/// A set of [Component]s which can be attached to (or removed from)
+/// an entity at once.
+///
+/// Inserting a bundle moves the entity into its new archetype only once,
+/// instead of once per component as it happens with
+/// [`World::add_component_to_entity`](crate::world::World::add_component_to_entity).
+///
+/// The trait is implemented for every component, for tuples of bundles,
+/// and can be implemented for structs of components using the
+/// [`Bundle`](ecs_macros::Bundle) derive macro.
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// #[derive(Bundle)]
+/// struct PlayerBundle {
+///     position: Position,
+///     health: Health,
+/// }
+///
+/// let player = world.spawn(PlayerBundle { ... });
+/// let enemy = world.spawn((Position { ... }, Health(50)));
+/// world.remove_bundle::<PlayerBundle>(player);
+/// ```
+///
+pub trait Bundle: 'static {
+    ///
+    /// ### Description
+    ///
+    /// Records the component types of the bundle, in the same order in
+    /// which the components are written by [Bundle::write_components]
+    fn collect_component_types(types: &mut BundleComponentTypes);
+
+    /// Writes every component of the bundle into the storage of the entity
+    fn write_components(self, writer: &mut BundleWriter);
+}
+
+impl<C: Component + 'static> Bundle for C {
+    fn collect_component_types(types: &mut BundleComponentTypes) {
+        types.add::<C>();
+    }
+
+    fn write_components(self, writer: &mut BundleWriter) {
+        writer.write(self);
+    }
+}
+
+macro_rules! bundle_tuples {
+    ($($param: ident),*) => {
+
+        #[allow(non_snake_case, unused_variables)]
+        impl<$($param: Bundle),*> Bundle for ($($param,)*) {
+            fn collect_component_types(types: &mut BundleComponentTypes) {
+                $($param::collect_component_types(types);)*
+            }
+
+            fn write_components(self, writer: &mut BundleWriter) {
+                let ($($param,)*) = self;
+                $($param.write_components(writer);)*
+            }
+        }
+    }
+}
+
+bundle_tuples!();
+ecs_macros::implement_tuples!(bundle_tuples, 0, 20, B);
+
+///
+/// ### Description
+///
+/// Type erased description of a component type which is a part of a [Bundle]
+///
+pub(crate) struct BundleComponent {
+    pub(crate) type_id: TypeId,
+    pub(crate) name: String,
+    pub(crate) storage_type: StorageType,
+
+    /// Creates an empty column for the component type, used when the
+    /// bundle insertion creates a new archetype
+    pub(crate) new_column: fn() -> Box<dyn ComponentColumn>,
+}
+
+///
+/// ### Description
+///
+/// The component types of a [Bundle], collected through
+/// [Bundle::collect_component_types]
+///
+pub struct BundleComponentTypes {
+    components: Vec<BundleComponent>,
+}
+
+impl BundleComponentTypes {
+    pub(crate) fn of<B: Bundle>() -> Self {
+        let mut types = Self { components: vec![] };
+        B::collect_component_types(&mut types);
+        types
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Records the component type [`C`] in the bundle
+    ///
+    /// WARNING: A bundle containing the same component type more
+    ///     than once will result in a panic
+    pub fn add<C: Component + 'static>(&mut self) {
+        assert!(
+            !self.contains(TypeId::of::<C>()),
+            "Bundle creation failed: Duplicate component [{}] found in the bundle",
+            C::get_name()
+        );
+
+        self.components.push(BundleComponent {
+            type_id: TypeId::of::<C>(),
+            name: C::get_name(),
+            storage_type: C::storage_type(),
+            new_column: || Box::new(Column::<C>::new()),
+        });
+    }
+
+    pub fn contains(&self, component_id: TypeId) -> bool {
+        self.components
+            .iter()
+            .any(|component| component.type_id == component_id)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &BundleComponent> {
+        self.components.iter()
+    }
+
+    /// Returns the components of the bundle using the given storage type
+    pub(crate) fn with_storage(
+        &self,
+        storage_type: StorageType,
+    ) -> impl Iterator<Item = &BundleComponent> {
+        self.components
+            .iter()
+            .filter(move |component| component.storage_type == storage_type)
+    }
+}
+
+///
+/// ### Description
+///
+/// Writes the components of a [Bundle] into the storages of an entity.
+///
+/// Dense components are pushed into the columns of the archetype which
+/// the entity has been moved into, while sparse components are inserted
+/// into their sparse sets.
+///
+pub struct BundleWriter<'a> {
+    entity_id: Entity,
+
+    /// Tick recorded as the addition tick of the written components
+    tick: Tick,
+
+    archetype: &'a mut Archetype,
+    sparse_sets: &'a mut HashMap<TypeId, Box<dyn ComponentSparseSet>>,
+}
+
+impl<'a> BundleWriter<'a> {
+    pub(crate) fn new(
+        entity_id: Entity,
+        tick: Tick,
+        archetype: &'a mut Archetype,
+        sparse_sets: &'a mut HashMap<TypeId, Box<dyn ComponentSparseSet>>,
+    ) -> Self {
+        Self {
+            entity_id,
+            tick,
+            archetype,
+            sparse_sets,
+        }
+    }
+
+    pub fn write<C: Component + 'static>(&mut self, component: C) {
+        match C::storage_type() {
+            StorageType::Dense => self
+                .archetype
+                .get_column_mut::<C>()
+                .unwrap()
+                .push(component, self.tick),
+            StorageType::Sparse => self
+                .sparse_sets
+                .get_mut(&TypeId::of::<C>())
+                .unwrap()
+                .as_any_mut()
+                .downcast_mut::<SparseSet<C>>()
+                .unwrap()
+                .insert(self.entity_id, component, self.tick),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::{Bundle, Component};
+
+    use super::*;
+    use crate::{ecs_base::ECSBase, entity::Entity, world::World};
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Position(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Velocity(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "sparse")]
+    struct Stunned;
+
+    #[derive(Bundle)]
+    struct Movement {
+        position: Position,
+        velocity: Velocity,
+    }
+
+    #[derive(Bundle)]
+    struct Player {
+        movement: Movement,
+        stunned: Stunned,
+    }
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Position>();
+        world.register_component::<Velocity>();
+        world.register_component::<Stunned>();
+        world
+    }
+
+    fn assert_movement(world: &World, entity_id: Entity, position: u32, velocity: u32) {
+        assert_eq!(world.entity(entity_id).get::<Position>(), Some(&Position(position)));
+        assert_eq!(world.entity(entity_id).get::<Velocity>(), Some(&Velocity(velocity)));
+    }
+
+    #[test]
+    fn spawn_attaches_nested_bundles() {
+        let mut world = new_world();
+        let tuple = world.spawn((Position(1), Velocity(2)));
+        let player = world.spawn(Player {
+            movement: Movement {
+                position: Position(3),
+                velocity: Velocity(4),
+            },
+            stunned: Stunned,
+        });
+
+        assert_movement(&world, tuple, 1, 2);
+        assert!(!world.has_component::<Stunned>(tuple));
+        assert_movement(&world, player, 3, 4);
+        assert!(world.has_component::<Stunned>(player));
+    }
+
+    #[test]
+    fn bundles_are_inserted_and_removed_together() {
+        let mut world = new_world();
+        let entity_id = world.spawn(Stunned);
+
+        world.insert_bundle(entity_id, (Position(1), Velocity(2)));
+        assert_movement(&world, entity_id, 1, 2);
+
+        // Components of the bundle missing from the entity are ignored
+        world.remove_component_from_entity::<Velocity>(entity_id);
+        world.remove_bundle::<Player>(entity_id);
+        assert_eq!(world.entity(entity_id).components().len(), 0);
+        assert!(world.is_alive(entity_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate component [Position] found in the bundle")]
+    fn duplicate_components_in_a_bundle_panic() {
+        let mut world = new_world();
+        world.spawn((Position(1), (Velocity(2), Position(3))));
+    }
+}
@@ -0,0 +1,24 @@
+use super::Component;
+use crate::{entity::Entity, world::World};
+
+/// Clones the component of a type attached to the source entity
+/// (first parameter) into the target entity (second parameter)
+pub type ComponentCloneFunction = fn(&mut World, Entity, Entity);
+
+///
+/// ### Description
+///
+/// [ComponentCloneFunction] of the cloneable component types, inserting
+/// a clone of the component of the source entity into the target entity.
+///
+/// A component type is made cloneable through the derive macro using the
+/// `#[component(clone)]` attribute, or through
+/// [World::register_cloneable]
+///
+pub fn clone_component<C: Component + Clone + 'static>(world: &mut World, source: Entity, target: Entity) {
+    let component = match world.get_component::<C>(source) {
+        Some(component) => component.clone(),
+        None => return,
+    };
+    world.insert_component(target, component);
+}
@@ -0,0 +1,156 @@
+use std::{cell::UnsafeCell, sync::Arc};
+
+use ecs_macros::ECSBase;
+
+use super::{
+    borrow::BorrowFlag,
+    tick::{ComponentTicks, Tick},
+    Component,
+};
+use crate::ecs_base::ECSBase;
+
+///
+/// ### Description
+///
+/// Type erased interface of a [Column] which is exposed to the
+/// [`archetype`](crate::archetype::Archetype) tables.
+///
+/// An archetype stores one column per component type it contains,
+/// and since the component types of an archetype are only known at
+/// runtime, the archetype can only operate on its columns through
+/// this interface.
+///
+/// All the rows of the columns in a single archetype are kept aligned
+/// with the entity list of the archetype, hence every row removal must
+/// be a swap removal to keep the columns in sync.
+///
+pub trait ComponentColumn: ECSBase {
+    ///
+    /// ### Description
+    ///
+    /// Creates a new empty column storing the same type of component
+    /// as this column.
+    ///
+    /// Used when a new archetype is created by adding or removing a
+    /// component from an entity of an existing archetype.
+    fn new_empty(&self) -> Box<dyn ComponentColumn>;
+
+    /// Removes the component at the given row and drops it.
+    fn swap_remove(&mut self, row: usize);
+
+    ///
+    /// ### Description
+    ///
+    /// Removes the component at the given row and pushes it at the end
+    /// of the `destination` column.
+    ///
+    /// WARNING: The destination column must store the same type of
+    ///     component as this column, otherwise the function will panic
+    fn move_row(&mut self, row: usize, destination: &mut dyn ComponentColumn);
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+///
+/// ### Description
+///
+/// Contiguous storage of a single type of [Component] inside of
+/// an [`archetype`](crate::archetype::Archetype) table.
+///
+/// The component at a given row belongs to the entity present at the
+/// same row in the entity list of the owning archetype.
+///
+/// #### SAFETY:
+/// The components are stored in an [UnsafeCell] without any per-component
+/// lock. Systems must acquire a [`borrow`](super::borrow::ColumnBorrow) on
+/// the [`borrow flag`](Column::borrow_flag) of the column before accessing
+/// the components through the pointers returned by this column.
+///
+/// Structural changes (pushing or removing rows) require a mutable
+/// reference to the column, which is only available to the
+/// [`world`](crate::world::World) outside of system execution.
+///
+#[derive(ECSBase)]
+pub(crate) struct Column<C: Component + 'static> {
+    components: Vec<UnsafeCell<C>>,
+
+    /// Change ticks of the components, in the same row order
+    ticks: Vec<UnsafeCell<ComponentTicks>>,
+
+    /// Column level borrow tracking, replacing per-component locks
+    borrow_flag: Arc<BorrowFlag>,
+}
+
+impl<C: Component + 'static> Column<C> {
+    pub fn new() -> Self {
+        Self {
+            components: vec![],
+            ticks: vec![],
+            borrow_flag: BorrowFlag::new(),
+        }
+    }
+
+    /// Pushes the component at the end of the column, recording
+    /// the given tick as the tick at which it was added
+    pub fn push(&mut self, component: C, tick: Tick) {
+        self.components.push(UnsafeCell::new(component));
+        self.ticks.push(UnsafeCell::new(ComponentTicks::new(tick)));
+    }
+
+    /// Replaces the component stored at the given row, recording
+    /// the given tick as the tick at which it was changed
+    pub fn replace(&mut self, row: usize, component: C, tick: Tick) {
+        *self.components[row].get_mut() = component;
+        self.ticks[row].get_mut().set_changed(tick);
+    }
+
+    pub fn borrow_flag(&self) -> &Arc<BorrowFlag> {
+        &self.borrow_flag
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns a pointer to the component stored at the given row.
+    ///
+    /// The pointer may only be dereferenced mutably while the column is
+    /// borrowed mutably, and immutably while the column is borrowed.
+    pub fn get_ptr(&self, row: usize) -> *mut C {
+        self.components[row].get()
+    }
+
+    /// Returns a pointer to the change ticks of the component stored
+    /// at the given row. See [Column::get_ptr] for the access rules.
+    pub fn get_ticks_ptr(&self, row: usize) -> *mut ComponentTicks {
+        self.ticks[row].get()
+    }
+}
+
+impl<C: Component + 'static> ComponentColumn for Column<C> {
+    fn new_empty(&self) -> Box<dyn ComponentColumn> {
+        Box::new(Column::<C>::new())
+    }
+
+    fn swap_remove(&mut self, row: usize) {
+        self.components.swap_remove(row);
+        self.ticks.swap_remove(row);
+    }
+
+    fn move_row(&mut self, row: usize, destination: &mut dyn ComponentColumn) {
+        let destination = destination
+            .as_any_mut()
+            .downcast_mut::<Column<C>>()
+            .expect("Column move failed: Destination column stores a different component type");
+
+        destination.components.push(self.components.swap_remove(row));
+        destination.ticks.push(self.ticks.swap_remove(row));
+    }
+
+    fn len(&self) -> usize {
+        self.components.len()
+    }
+}
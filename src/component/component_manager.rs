@@ -1,295 +1,232 @@
-use ecs_macros::ECSBase;
+use super::{
+    clone::ComponentCloneFunction,
+    dynamic::{ComponentDescriptor, DropFunction},
+    hooks::ComponentHooks,
+    required::RequiredComponents,
+    Component, StorageType,
+};
 
-use super::Component;
-use crate::{ecs_base::ECSBase, entity::Entity};
-
-use std::{any::TypeId, sync::Arc};
-
-use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock};
+use std::{alloc::Layout, any::TypeId, sync::Arc};
 
 use hashbrown::HashMap;
 
 ///
 /// ### Description
 ///
-/// Interface of Component Manager which is to be exposed to the world
-/// struct.
+/// Compact identifier of a component type registered in a
+/// [`world`](crate::world::World), assigned in registration order.
+///
+/// Unlike a [TypeId], a component id also identifies the component
+/// types defined at runtime, see [ComponentDescriptor].
 ///
-pub trait EcsManager: ECSBase {
-    fn new() -> Self
-    where
-        Self: Sized;
-    fn remove_component_from_entity(&mut self, entity_id: Entity);
-    fn has_component(&self, entity_id: Entity) -> bool;
-    fn get_entities(&self) -> Vec<&Entity>;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ComponentId(u32);
+
+impl ComponentId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
 }
 
 ///
 /// ### Description
-/// Structure for managing a components of a specific type
-///
-/// This is an internal structure which is stored in the [`world`](crate::App::world), and is responsible
-/// for storing, modifying and ensuring safety of components created in a given world
-/// The components stored in this struct are identified using their entity_id, where we
-/// create a B-Tree structure to quickly trace a component in the array
 ///
+/// Information recorded about a [Component] type when it is
+/// registered in a [`world`](crate::world::World)
 ///
+pub struct ComponentInfo {
+    id: ComponentId,
+
+    /// None for the component types defined at runtime
+    type_id: Option<TypeId>,
+
+    name: String,
+    storage_type: StorageType,
+    layout: Layout,
+    drop: Option<DropFunction>,
+    hooks: ComponentHooks,
+    clone: Option<ComponentCloneFunction>,
+
+    /// Shared, since the requirements are read while the world is
+    /// being mutated by the insertion of the required components
+    required_components: Arc<RequiredComponents>,
+}
+
+impl ComponentInfo {
+    pub fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    pub fn type_id(&self) -> Option<TypeId> {
+        self.type_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn storage_type(&self) -> StorageType {
+        self.storage_type
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn drop(&self) -> Option<DropFunction> {
+        self.drop
+    }
+
+    /// Returns true if the component type was defined at runtime
+    pub fn is_dynamic(&self) -> bool {
+        self.type_id.is_none()
+    }
+
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
+    }
+
+    /// Returns the function cloning the component, if the component type is cloneable
+    pub fn clone_function(&self) -> Option<ComponentCloneFunction> {
+        self.clone
+    }
+
+    pub fn required_components(&self) -> &Arc<RequiredComponents> {
+        &self.required_components
+    }
+}
+
+/// Drops a value of type [`C`] through a type erased pointer
+unsafe fn drop_component<C>(ptr: *mut u8) {
+    ptr.cast::<C>().drop_in_place();
+}
+
 ///
-/// This structure implements the base [EcsManager] interface because
-/// a base trait is required for storing managers in the world, since
-/// each manager has a different component type which cannot be known
-/// during declaration, i.e. compile time.
+/// ### Description
 ///
-/// #### SAFETY:
-/// Also, each [`component`](ComponentManager::components) is stored as a [RefCell], which ensures
-/// that the component does not have illegal references anywhere in the program.
+/// Structure responsible for keeping a record of the [Component] types
+/// registered in a [`world`](crate::world::World)
 ///
+/// The components themselves are stored in the
+/// [`archetype`](crate::archetype::Archetype) tables. A component type
+/// must still be registered in this manager before it can be attached
+/// to any entity in the world.
 ///
+pub(crate) struct ComponentManager {
+    /// Information of the component types, indexed by [ComponentId]
+    components: Vec<ComponentInfo>,
 
-#[derive(ECSBase)]
-pub(crate) struct ComponentManager<Comp>
-where
-    Comp: Component,
-{
-    /// Component storage vector which stores [Arc] of [RwLock].
-    /// The [Arc] helps in keeping the memory alive untill all references
-    /// have been invalidated, whereas the [RwLock] is essential for ensuring
-    /// appropriate access across threads
-    components: Vec<Arc<RwLock<Comp>>>,
-
-    /// Stores the [entity ids](Entity) of the [`components`](ComponentManager::components)
-    /// in the same order as the components are presented in the array
-    /// above.
-    entity_ids: Vec<Entity>,
-
-    /// [Entity] to 'index' mapping to boost lookup
-    ///
-    /// NOTE: We use [Entity] to identify a component in the system since
-    ///     each component in existence has to be attached to an entity,
-    ///     therefore for a component system managing a specific type of component,
-    ///     each component is attached to a unique entity, hence we can use
-    ///     entity id as the identifier for the components
-    entity_component_map: HashMap<Entity, usize>,
+    /// Ids of the statically typed components
+    ids: HashMap<TypeId, ComponentId>,
 }
 
-impl<Comp> EcsManager for ComponentManager<Comp>
-where
-    Comp: Component + 'static,
-{
-    /// ### Description
-    ///
-    /// Used to create a new manager to handle the specified type of component
-    fn new() -> Self {
-        ComponentManager {
+impl ComponentManager {
+    pub fn new() -> Self {
+        Self {
             components: vec![],
-            entity_ids: vec![],
-            entity_component_map: HashMap::new(),
+            ids: HashMap::new(),
         }
     }
 
+    ///
     /// ### Description
     ///
-    /// Removes a component from the entity if the component was attached,
-    fn remove_component_from_entity(&mut self, entity_id: Entity) {
-        
-        // Entity must have a component of type [`Comp`] attached for removal to be possible
-        if !self.entity_component_map.contains_key(&entity_id) {
-            let err_str = format!(
-                "Entity [{:?}] does not have a component of type \'{}\'",
-                entity_id,
-                Comp::get_name()
-            );
-            log::warn!("{err_str}");
-            return;
-        }
-
-        let components_length = self.components.len();
-        let component_index = self.entity_component_map.get(&entity_id).unwrap();
-
-        // O(1) removal time, without disturbing majority of the elements indexes
-        // Removed element is replaced by the last element in the array
-        self.components.swap_remove(*component_index);
-        self.entity_ids.swap_remove(*component_index);
-
-        // Updating the lookup table for the replaced indexes only if any element
-        // in the middle of the array was removed.
-        if components_length - 1 != *component_index {
-            self.entity_component_map
-                .insert(self.entity_ids[*component_index], *component_index)
-                .unwrap();
+    /// Records the component type in the manager.
+    ///
+    /// ### Return Value
+    /// [`false`](bool) if the component type was already registered,
+    /// [`true`](bool) otherwise
+    pub fn register_component<C: Component + 'static>(&mut self) -> bool {
+        if self.is_registered(TypeId::of::<C>()) {
+            return false;
         }
 
-        // Removing removed entity_id from lookup
-        self.entity_component_map.remove(&entity_id).unwrap();
+        let mut hooks = ComponentHooks::default();
+        C::register_hooks(&mut hooks);
+
+        let mut required_components = RequiredComponents::new();
+        C::register_required_components(&mut required_components);
+
+        let id = self.next_id();
+        self.ids.insert(TypeId::of::<C>(), id);
+        self.components.push(ComponentInfo {
+            id,
+            type_id: Some(TypeId::of::<C>()),
+            name: C::get_name(),
+            storage_type: C::storage_type(),
+            layout: Layout::new::<C>(),
+            drop: std::mem::needs_drop::<C>().then_some(drop_component::<C> as DropFunction),
+            hooks,
+            clone: C::clone_function(),
+            required_components: Arc::new(required_components),
+        });
+        true
     }
 
     ///
-    /// ### Description:
+    /// ### Description
     ///
-    /// Used to find whether the component is attached to the [`entity_id`](Entity)
+    /// Records a component type defined at runtime in the manager.
     ///
-    /// ### Return Value:
-    /// [`true`](bool) if the component is present in the entity
-    /// [`false`](bool) otherwise
-    fn has_component(&self, entity_id: Entity) -> bool {
-        self.entity_component_map.contains_key(&entity_id)
+    /// Dynamic components are always stored in sparse storages, and
+    /// have neither hooks nor required components
+    pub fn register_dynamic_component(&mut self, descriptor: ComponentDescriptor) -> ComponentId {
+        let id = self.next_id();
+        self.components.push(ComponentInfo {
+            id,
+            type_id: None,
+            name: descriptor.name,
+            storage_type: StorageType::Sparse,
+            layout: descriptor.layout,
+            drop: descriptor.drop,
+            hooks: ComponentHooks::default(),
+            clone: None,
+            required_components: Arc::new(RequiredComponents::new()),
+        });
+        id
     }
 
-    ///
-    /// ### Description
-    ///
-    /// Returns an array of all active [`EntityId`](Entity)s which have
-    /// the [`Component`] of type ['Comp'](ComponentManager<Comp>) attached to them
-    fn get_entities(&self) -> Vec<&Entity> {
-        self.entity_ids.iter().collect()
+    pub fn is_registered(&self, component_id: TypeId) -> bool {
+        self.ids.contains_key(&component_id)
     }
-}
 
-/// Public functions of the ComponentSystem struct
-impl<Comp> ComponentManager<Comp>
-where
-    Comp: Component + 'static,
-{
-    /// ### Description
-    ///
-    /// Adds a component into the system based on the stack-build object passed
-    /// as a parameter
-    pub fn add_component_to_entity(&mut self, entity_id: Entity, component: Comp) {
-        assert!(
-            !self.entity_component_map.contains_key(&entity_id),
-            "Component addition to Entity [{:?}] failed: Duplicate components are not allowed in entities.", 
-            entity_id
-        );
-
-        let component_index = self.components.len();
-
-        self.components.push(Arc::new(RwLock::new(component)));
-        self.entity_ids.push(entity_id);
-        self.entity_component_map.insert(entity_id, component_index);
+    pub fn get_id(&self, component_id: TypeId) -> Option<ComponentId> {
+        self.ids.get(&component_id).copied()
     }
 
-    ///
-    /// ### Description
-    /// 
-    /// Returns a read lock into the component of type [`Comp`]
-    /// in the form of an option
-    /// 
-    /// If the component is not attached to the entity, the option
-    /// is returned as None
-    /// 
-    pub fn borrow_component(&self, entity_id: Entity) -> Option<OwnedRwLockReadGuard<Comp>> {
-        let component_index = match self.entity_component_map.get(&entity_id) {
-            Some(x) => x,
-            None => {
-                log::error!("borrow_component_mut failed:");
-                log::error!(
-                    "Component [id: {:?}] is not attached to Entity [id: {:?}]",
-                    TypeId::of::<Comp>(),
-                    entity_id
-                );
-                return None;
-            }
-        };
-
-        match self.components[*component_index].clone().try_read_owned() {
-            Ok(x) => Some(x),
-            Err(err) => {
-                // Mutable reference is not possible
-                log::error!(
-                    "Component [id: {:?}, type: {:?}] -> Mutable reference not possible. \n{}",
-                    entity_id,
-                    TypeId::of::<Comp>(),
-                    err.to_string()
-                );
-                None
-            }
-        }
+    /// Returns the id of the first component type registered with the given name
+    pub fn get_id_by_name(&self, name: &str) -> Option<ComponentId> {
+        self.components
+            .iter()
+            .find(|info| info.name == name)
+            .map(|info| info.id)
     }
 
-    ///
-    /// ### Description
-    /// 
-    /// Returns a write lock into the component of type [`Comp`]
-    /// in the form of an option.
-    /// 
-    /// If the component is not attached to the entity, the option
-    /// is returned as None
-    /// 
-    pub fn borrow_component_mut(&self, entity_id: Entity) -> Option<OwnedRwLockWriteGuard<Comp>> {
-        let component_index = match self.entity_component_map.get(&entity_id) {
-            Some(x) => x,
-            None => {
-                log::error!("borrow_component_mut failed:");
-                log::error!(
-                    "Component [id: {:?}] is not attached to Entity [id: {:?}]",
-                    TypeId::of::<Comp>(),
-                    entity_id
-                );
-                return None;
-            }
-        };
-
-        match self.components[*component_index].clone().try_write_owned() {
-            Ok(x) => Some(x),
-            Err(err) => {
-                // Mutable reference is not possible
-                log::error!(
-                    "Component [id: {:?}, type: {:?}] -> Mutable reference not possible. \n{}",
-                    entity_id,
-                    TypeId::of::<Comp>(),
-                    err.to_string()
-                );
-                None
-            }
-        }
+    pub fn get_info(&self, component_id: TypeId) -> Option<&ComponentInfo> {
+        self.get_info_by_id(self.get_id(component_id)?)
     }
 
-
-    /// 
-    /// ### Description
-    /// 
-    /// Returns all components in the manager as a [Option<Vector>] of read locks
-    /// to provide read access into all components manager by this 
-    /// component manager.
-    /// 
-    /// If any one of the lock acquisitions fail, we return None inside
-    /// the option
-    /// 
-    pub fn borrow_all_components(&self) -> Option<Vec<(Entity, OwnedRwLockReadGuard<Comp>)>> {
-        let mut vec = vec![];
-        for (index, component) in self.components.iter().enumerate() {
-            let value = match component.clone().try_read_owned() {
-                Ok(x) => x,
-                Err(_) => return None,
-            };
-
-            vec.push((self.entity_ids[index], value));
-        }
-        Some(vec)
+    pub fn get_info_by_id(&self, id: ComponentId) -> Option<&ComponentInfo> {
+        self.components.get(id.index())
     }
 
+    pub fn get_hooks_mut(&mut self, component_id: TypeId) -> Option<&mut ComponentHooks> {
+        let id = self.get_id(component_id)?;
+        Some(&mut self.components[id.index()].hooks)
+    }
 
-    /// 
-    /// ### Description
-    /// 
-    /// Returns all components in the manager as a [Option<Vector>] 
-    /// of write locks to provide read access into all components 
-    /// manager by this component manager.
-    /// 
-    /// If any one of the lock acquisitions fail, we return None inside
-    /// the option
-    /// 
-    pub fn borrow_all_components_mut(&self) -> Option<Vec<(Entity, OwnedRwLockWriteGuard<Comp>)>> {
-        let mut vec = vec![];
-        for (index, component) in self.components.iter().enumerate() {
-            let value = match component.clone().try_write_owned() {
-                Ok(x) => x,
-                Err(_) => return None,
-            };
-
-            vec.push((self.entity_ids[index], value));
-            // vec.push(value);
+    pub fn set_clone_function(&mut self, component_id: TypeId, clone: ComponentCloneFunction) -> bool {
+        match self.get_id(component_id) {
+            Some(id) => {
+                self.components[id.index()].clone = Some(clone);
+                true
+            }
+            None => false,
         }
-        Some(vec)
+    }
+
+    fn next_id(&self) -> ComponentId {
+        let index = u32::try_from(self.components.len()).expect("Component registration failed: Too many component types");
+        ComponentId(index)
     }
 }
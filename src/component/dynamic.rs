@@ -0,0 +1,353 @@
+use std::{
+    alloc::{self, Layout},
+    ptr::NonNull,
+};
+
+use crate::entity::Entity;
+
+/// Drops a value of a component type in place, given a pointer to the value
+pub type DropFunction = unsafe fn(*mut u8);
+
+///
+/// ### Description
+///
+/// Description of a component type which is defined at runtime, such as
+/// a component type of a scripting or modding layer.
+///
+/// A dynamic component is registered through
+/// [`World::register_dynamic_component`](crate::world::World::register_dynamic_component),
+/// which returns the [`ComponentId`](super::component_manager::ComponentId) used
+/// to insert, read and write the bytes of the component.
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// let health = world.register_dynamic_component(ComponentDescriptor::new(
+///     "Health",
+///     Layout::new::<f32>(),
+///     None,
+/// ));
+///
+/// // @SAFETY: Any 4 bytes form a valid f32
+/// unsafe { world.insert_component_bytes(entity, health, &100.0f32.to_ne_bytes()) };
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct ComponentDescriptor {
+    pub(crate) name: String,
+    pub(crate) layout: Layout,
+    pub(crate) drop: Option<DropFunction>,
+}
+
+impl ComponentDescriptor {
+    ///
+    /// ### Description
+    ///
+    /// Creates the description of a component type with the given name
+    /// and memory layout.
+    ///
+    /// The drop function is called with a pointer to every value of the
+    /// component type which is removed, replaced or dropped along with
+    /// the world. Plain data components do not need a drop function.
+    pub fn new(name: impl Into<String>, layout: Layout, drop: Option<DropFunction>) -> Self {
+        Self {
+            name: name.into(),
+            layout,
+            drop,
+        }
+    }
+}
+
+///
+/// ### Description
+///
+/// Type erased vector of values sharing a single [Layout]
+///
+struct BlobVec {
+    item_layout: Layout,
+    drop: Option<DropFunction>,
+    data: NonNull<u8>,
+    len: usize,
+    capacity: usize,
+}
+
+impl BlobVec {
+    fn new(item_layout: Layout, drop: Option<DropFunction>) -> Self {
+        // Zero sized values never need an allocation
+        let capacity = if item_layout.size() == 0 { usize::MAX } else { 0 };
+
+        Self {
+            item_layout,
+            drop,
+            data: NonNull::new(std::ptr::without_provenance_mut(item_layout.align())).unwrap(),
+            len: 0,
+            capacity,
+        }
+    }
+
+    /// Distance in bytes between two consecutive values
+    fn stride(&self) -> usize {
+        self.item_layout.pad_to_align().size()
+    }
+
+    fn array_layout(&self, capacity: usize) -> Layout {
+        Layout::from_size_align(self.stride() * capacity, self.item_layout.align())
+            .expect("Dynamic component storage overflow")
+    }
+
+    fn reserve_one(&mut self) {
+        if self.len < self.capacity {
+            return;
+        }
+
+        let new_capacity = usize::max(4, self.capacity * 2);
+        let new_layout = self.array_layout(new_capacity);
+
+        // @SAFETY: The layout has a non zero size, since zero sized
+        //      values never reach this point
+        let data = unsafe {
+            if self.capacity == 0 {
+                alloc::alloc(new_layout)
+            } else {
+                alloc::realloc(self.data.as_ptr(), self.array_layout(self.capacity), new_layout.size())
+            }
+        };
+
+        self.data = NonNull::new(data).unwrap_or_else(|| alloc::handle_alloc_error(new_layout));
+        self.capacity = new_capacity;
+    }
+
+    fn get_ptr(&self, index: usize) -> *mut u8 {
+        // @SAFETY: The offset stays inside of the allocation for indices below the capacity
+        unsafe { self.data.as_ptr().add(index * self.stride()) }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Copies the value into the end of the vector, taking ownership of it
+    ///
+    /// @SAFETY: The value must be valid for reads of the size of the item layout
+    unsafe fn push(&mut self, value: *const u8) {
+        self.reserve_one();
+        std::ptr::copy_nonoverlapping(value, self.get_ptr(self.len), self.item_layout.size());
+        self.len += 1;
+    }
+
+    /// Drops the value at the given index and replaces it with the given value.
+    /// See [BlobVec::push] for the safety requirements
+    unsafe fn replace(&mut self, index: usize, value: *const u8) {
+        let ptr = self.get_ptr(index);
+        if let Some(drop) = self.drop {
+            drop(ptr);
+        }
+        std::ptr::copy_nonoverlapping(value, ptr, self.item_layout.size());
+    }
+
+    /// Drops the value at the given index, and moves the last value into its place
+    fn swap_remove_and_drop(&mut self, index: usize) {
+        let last = self.len - 1;
+        let ptr = self.get_ptr(index);
+
+        // @SAFETY: Both indices are below the length of the vector
+        unsafe {
+            if let Some(drop) = self.drop {
+                drop(ptr);
+            }
+            if index != last {
+                std::ptr::copy_nonoverlapping(self.get_ptr(last), ptr, self.item_layout.size());
+            }
+        }
+        self.len -= 1;
+    }
+}
+
+impl Drop for BlobVec {
+    fn drop(&mut self) {
+        if let Some(drop) = self.drop {
+            for index in 0..self.len {
+                // @SAFETY: Every value below the length is initialised
+                unsafe { drop(self.get_ptr(index)) };
+            }
+        }
+
+        if self.item_layout.size() != 0 && self.capacity != 0 {
+            // @SAFETY: The data was allocated with the layout of the current capacity
+            unsafe { alloc::dealloc(self.data.as_ptr(), self.array_layout(self.capacity)) };
+        }
+    }
+}
+
+// @SAFETY: Dynamic components are only accessed through the world, which
+//      follows the same access rules as for statically typed components
+unsafe impl Send for BlobVec {}
+unsafe impl Sync for BlobVec {}
+
+///
+/// ### Description
+///
+/// Storage for the components of a single dynamic component type,
+/// following the same layout as a [`SparseSet`](super::sparse_set::SparseSet):
+/// the values are packed in a type erased vector, while a sparse vector
+/// indexed by the [`entity index`](Entity::index) maps each entity to
+/// its value.
+///
+pub(crate) struct DynamicSparseSet {
+    /// Densely packed component values
+    values: BlobVec,
+
+    /// Entities owning the values, in the same order as the values
+    entities: Vec<Entity>,
+
+    /// Entity index to dense index mapping
+    sparse: Vec<Option<usize>>,
+}
+
+impl DynamicSparseSet {
+    pub fn new(layout: Layout, drop: Option<DropFunction>) -> Self {
+        Self {
+            values: BlobVec::new(layout, drop),
+            entities: vec![],
+            sparse: vec![],
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Attaches the value to the entity, replacing (and dropping) the
+    /// value already attached to the entity, if any
+    ///
+    /// @SAFETY: The value must be valid for reads of the size of the
+    ///     component layout, and is owned by the set after the call
+    pub unsafe fn insert(&mut self, entity_id: Entity, value: *const u8) {
+        if let Some(dense_index) = self.get_dense_index(entity_id) {
+            self.values.replace(dense_index, value);
+            return;
+        }
+
+        let sparse_index = entity_id.index() as usize;
+        if sparse_index >= self.sparse.len() {
+            self.sparse.resize(sparse_index + 1, None);
+        }
+
+        self.sparse[sparse_index] = Some(self.entities.len());
+        self.values.push(value);
+        self.entities.push(entity_id);
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Removes and drops the value attached to the entity
+    ///
+    /// ### Return Value
+    /// [`false`](bool) if no value was attached to the entity,
+    /// [`true`](bool) otherwise
+    pub fn remove(&mut self, entity_id: Entity) -> bool {
+        let dense_index = match self.get_dense_index(entity_id) {
+            Some(dense_index) => dense_index,
+            None => return false,
+        };
+
+        self.values.swap_remove_and_drop(dense_index);
+        self.entities.swap_remove(dense_index);
+        self.sparse[entity_id.index() as usize] = None;
+
+        // Updating the lookup of the moved value
+        if dense_index < self.entities.len() {
+            let moved_entity = self.entities[dense_index];
+            self.sparse[moved_entity.index() as usize] = Some(dense_index);
+        }
+
+        true
+    }
+
+    pub fn contains(&self, entity_id: Entity) -> bool {
+        self.get_dense_index(entity_id).is_some()
+    }
+
+    /// Returns the entities which have a value in the set
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Returns a pointer to the value attached to the entity
+    pub fn get_ptr(&self, entity_id: Entity) -> Option<*mut u8> {
+        let dense_index = self.get_dense_index(entity_id)?;
+        Some(self.values.get_ptr(dense_index))
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.values.item_layout
+    }
+
+    /// Finds the index of the value attached to the entity, verifying
+    /// the generation of the entity. See [`SparseSet`](super::sparse_set::SparseSet)
+    fn get_dense_index(&self, entity_id: Entity) -> Option<usize> {
+        let dense_index = (*self.sparse.get(entity_id.index() as usize)?)?;
+
+        if self.entities[dense_index] != entity_id {
+            return None;
+        }
+        Some(dense_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        alloc::Layout,
+        sync::{mpsc::channel, Mutex},
+    };
+
+    use crate::world::World;
+
+    use super::ComponentDescriptor;
+
+    /// Values dropped through [record_drop]
+    static DROPPED: Mutex<Vec<u32>> = Mutex::new(vec![]);
+
+    unsafe fn record_drop(value: *mut u8) {
+        DROPPED.lock().unwrap().push((value as *mut u32).read_unaligned());
+    }
+
+    #[test]
+    fn dynamic_values_are_dropped() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        let component_id = world.register_dynamic_component(ComponentDescriptor::new(
+            "Counter",
+            Layout::new::<u32>(),
+            Some(record_drop),
+        ));
+
+        let first = world.spawn(());
+        let second = world.spawn(());
+        let third = world.spawn(());
+
+        // @SAFETY: Any 4 bytes form a valid u32
+        unsafe {
+            world.insert_component_bytes(first, component_id, &1u32.to_ne_bytes());
+            world.insert_component_bytes(second, component_id, &2u32.to_ne_bytes());
+            world.insert_component_bytes(third, component_id, &3u32.to_ne_bytes());
+
+            // Replaced values are dropped
+            world.insert_component_bytes(first, component_id, &4u32.to_ne_bytes());
+        }
+        assert_eq!(*DROPPED.lock().unwrap(), [1]);
+        assert_eq!(world.get_component_bytes(first, component_id), Some(&4u32.to_ne_bytes()[..]));
+
+        world.remove_component_by_id(second, component_id);
+        assert_eq!(*DROPPED.lock().unwrap(), [1, 2]);
+
+        world.remove_entity(third);
+        assert_eq!(*DROPPED.lock().unwrap(), [1, 2, 3]);
+        assert_eq!(world.get_component_bytes(first, component_id), Some(&4u32.to_ne_bytes()[..]));
+
+        drop(world);
+        assert_eq!(*DROPPED.lock().unwrap(), [1, 2, 3, 4]);
+    }
+}
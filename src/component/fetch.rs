@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use super::{
+    borrow::BorrowFlag,
+    column::Column,
+    tick::ComponentTicks,
+    sparse_set::{ComponentSparseSet, SparseSet},
+    Component,
+};
+use crate::entity::Entity;
+
+///
+/// ### Description
+///
+/// Storage of a component type resolved for a single
+/// [`archetype`](crate::archetype::Archetype) during query iteration.
+///
+/// Queries resolve the storage of each requested component once per
+/// archetype, and then fetch the component of each entity in the
+/// archetype without having to dispatch on the
+/// [`storage type`](super::StorageType) again.
+///
+pub(crate) enum ComponentFetch<'a, C: Component + 'static> {
+    /// Column of the archetype table storing the component
+    Dense(&'a Column<C>),
+
+    /// Sparse set storing the component for all entities in the world
+    Sparse(&'a SparseSet<C>),
+}
+
+impl<C: Component + 'static> ComponentFetch<'_, C> {
+    ///
+    /// ### Description
+    ///
+    /// Returns true if the entity of the archetype has the
+    /// component attached.
+    ///
+    /// Dense components are present on every entity of a matching
+    /// archetype, hence only sparse components need to be checked
+    pub fn contains(&self, entity_id: Entity) -> bool {
+        match self {
+            Self::Dense(_) => true,
+            Self::Sparse(sparse_set) => sparse_set.contains(entity_id),
+        }
+    }
+
+    /// Returns the borrow flag of the resolved storage
+    pub fn borrow_flag(&self) -> &Arc<BorrowFlag> {
+        match self {
+            Self::Dense(column) => column.borrow_flag(),
+            Self::Sparse(sparse_set) => sparse_set.borrow_flag(),
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns a pointer to the component of the entity present at
+    /// the given row of the archetype.
+    ///
+    /// WARNING: The entity must have the component attached, see
+    ///     [ComponentFetch::contains]
+    pub fn get_ptr(&self, row: usize, entity_id: Entity) -> *mut C {
+        match self {
+            Self::Dense(column) => column.get_ptr(row),
+            Self::Sparse(sparse_set) => sparse_set.get_ptr(entity_id).unwrap(),
+        }
+    }
+
+    /// Returns a pointer to the change ticks of the component of the
+    /// entity, see [ComponentFetch::get_ptr]
+    pub fn get_ticks_ptr(&self, row: usize, entity_id: Entity) -> *mut ComponentTicks {
+        match self {
+            Self::Dense(column) => column.get_ticks_ptr(row),
+            Self::Sparse(sparse_set) => sparse_set.get_ticks_ptr(entity_id).unwrap(),
+        }
+    }
+}
@@ -1,100 +1,462 @@
-use std::ops::{Deref, DerefMut};
-use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard};
+use std::{
+    any::TypeId,
+    ops::{Deref, DerefMut},
+    slice,
+};
 
 use crate::entity::Entity;
 
-use super::Component;
+use super::{
+    tick::{ComponentTicks, Tick},
+    Component,
+};
 
 
 
-/// 
+///
 /// ### Description
-/// 
+///
 /// This Handle represents an immutable access acquired into a
 /// specific component from the world.
-/// 
+///
 /// This handle is an interface to be used by the user to gain
 /// immutable access into a component inside a [`system`](crate::System) function.
-/// 
-#[allow(dead_code)]
-pub struct ComponentHandle<C: Component + 'static> {
-    pub(crate) inner: OwnedRwLockReadGuard<C>,
+///
+/// The handle also gives access to the change ticks of the component,
+/// relative to the last run of the system which acquired the handle.
+/// See [ComponentHandle::is_added] and [ComponentHandle::is_changed]
+///
+/// The handle borrows from the [`system param`](crate::system::param::SystemParam)
+/// supplying it, which holds a borrow on the storage of the component.
+/// Hence, the handle can not outlive the param, nor be moved into a
+/// command.
+///
+pub struct ComponentHandle<'h, C: Component + 'static> {
+    inner: &'h C,
+    ticks: &'h ComponentTicks,
+    last_run: Tick,
+    this_run: Tick,
     pub entity_id: Entity,
 }
 
-impl<C: Component + 'static> ComponentHandle<C> {
-    pub fn new(lock: OwnedRwLockReadGuard<C>, entity_id: Entity) -> Self {
-        ComponentHandle {
-            entity_id,
-            inner: lock
-        }
+impl<'h, C: Component + 'static> ComponentHandle<'h, C> {
+    /// Returns the reference into the component, which lives
+    /// as long as the borrow of the param supplying the handle
+    pub fn into_inner(self) -> &'h C {
+        self.inner
+    }
+
+    /// Returns true if the component was attached to the entity
+    /// after the last run of the system
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_run, self.this_run)
+    }
+
+    /// Returns true if the component was added or mutated after
+    /// the last run of the system
+    pub fn is_changed(&self) -> bool {
+        self.ticks.is_changed(self.last_run, self.this_run)
     }
 }
-impl<C: Component + 'static> Deref for ComponentHandle<C> {
+impl<C: Component + 'static> Deref for ComponentHandle<'_, C> {
     type Target = C;
-    
+
     fn deref(&self) -> &Self::Target {
-        self.inner.deref()
+        self.inner
+    }
+}
+impl<C: Component + 'static> Clone for ComponentHandle<'_, C> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
+impl<C: Component + 'static> Copy for ComponentHandle<'_, C> {}
 
 
 
 
-/// 
+///
 /// ### Description
-/// 
+///
 /// This handle represents a mutable access acquired into
-/// specific component from the world. 
+/// specific component from the world.
 /// This handle is an interface object used by the user
 /// to mutate the world components in systems
-/// 
-pub struct MutComponentHandle<C: Component + 'static> {
-    pub(crate) inner: OwnedRwLockWriteGuard<C>,
+///
+/// Dereferencing the handle mutably marks the component as changed
+/// at the current run of the system.
+///
+/// The handle borrows from the param supplying it, see [ComponentHandle]
+///
+pub struct MutComponentHandle<'h, C: Component + 'static> {
+    inner: &'h mut C,
+    ticks: &'h mut ComponentTicks,
+    last_run: Tick,
+    this_run: Tick,
     pub entity_id: Entity,
 }
-impl<C: Component + 'static> MutComponentHandle<C> {
-    pub fn new(lock: OwnedRwLockWriteGuard<C>, entity_id: Entity) -> Self {
-        MutComponentHandle {
-            inner: lock,
-            entity_id
-        }
+impl<C: Component + 'static> MutComponentHandle<'_, C> {
+    /// See [ComponentHandle::is_added]
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_run, self.this_run)
+    }
+
+    /// See [ComponentHandle::is_changed]
+    pub fn is_changed(&self) -> bool {
+        self.ticks.is_changed(self.last_run, self.this_run)
     }
 }
-impl<C: Component + 'static> Deref for MutComponentHandle<C> {
+impl<C: Component + 'static> Deref for MutComponentHandle<'_, C> {
     type Target = C;
-    
+
     fn deref(&self) -> &Self::Target {
-        self.inner.deref()
+        self.inner
     }
 }
-impl<C: Component + 'static> DerefMut for MutComponentHandle<C> {
+impl<C: Component + 'static> DerefMut for MutComponentHandle<'_, C> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.inner.deref_mut()
+        self.ticks.set_changed(self.this_run);
+        self.inner
     }
 }
 
 
 
+
+///
+/// ### Description
+///
+/// A handle stored by a [`system param`](crate::system::param::SystemParam)
+/// for the duration of the system execution, out of which the param creates
+/// the handles borrowing from itself.
+///
+/// The param holds the borrows on the storages the handle points into,
+/// which is what makes the borrowed handles valid.
+///
+pub trait RawHandle {
+    /// Handle created out of a shared reference to the param
+    type Handle<'h> where Self: 'h;
+
+    /// Handle created out of a mutable reference to the param
+    type MutHandle<'h> where Self: 'h;
+
+    ///
+    /// ### Description
+    ///
+    /// Creates the handle borrowing from the raw handle.
+    ///
+    /// ### Safety
+    ///
+    /// The storages the raw handle points into must be
+    /// borrowed for as long as the returned handle lives
+    unsafe fn handle(&self) -> Self::Handle<'_>;
+
+    ///
+    /// ### Description
+    ///
+    /// Creates the handle mutably borrowing from the raw handle.
+    ///
+    /// ### Safety
+    ///
+    /// The storages the raw handle writes into must be mutably
+    /// borrowed for as long as the returned handle lives
+    unsafe fn mut_handle(&mut self) -> Self::MutHandle<'_>;
+
+    /// Returns a pointer to the component of the given type, if the
+    /// raw handle points into such a component
+    fn get_component_ptr(&self, component_id: TypeId) -> Option<*const u8>;
+}
+
 ///
 /// ### Description
-/// 
-/// This is the wrapper struct which allows us to access a 
-/// [`component`](Component) lock tuple acquired using the 
+///
+/// Raw handle into a component which is only read, creating a
+/// [ComponentHandle] whichever the reference to the param.
+///
+pub struct ComponentPtr<C: Component + 'static> {
+    component: *const C,
+    ticks: *const ComponentTicks,
+    last_run: Tick,
+    this_run: Tick,
+    entity_id: Entity,
+}
+
+impl<C: Component + 'static> ComponentPtr<C> {
+    pub(crate) fn new(
+        component: *const C,
+        ticks: *const ComponentTicks,
+        entity_id: Entity,
+        last_run: Tick,
+        this_run: Tick,
+    ) -> Self {
+        Self {
+            component,
+            ticks,
+            last_run,
+            this_run,
+            entity_id,
+        }
+    }
+}
+
+impl<C: Component + 'static> RawHandle for ComponentPtr<C> {
+    type Handle<'h> = ComponentHandle<'h, C>;
+    type MutHandle<'h> = ComponentHandle<'h, C>;
+
+    unsafe fn handle(&self) -> Self::Handle<'_> {
+        ComponentHandle {
+            inner: &*self.component,
+            ticks: &*self.ticks,
+            last_run: self.last_run,
+            this_run: self.this_run,
+            entity_id: self.entity_id,
+        }
+    }
+
+    unsafe fn mut_handle(&mut self) -> Self::MutHandle<'_> {
+        self.handle()
+    }
+
+    fn get_component_ptr(&self, component_id: TypeId) -> Option<*const u8> {
+        (component_id == TypeId::of::<C>()).then_some(self.component as *const u8)
+    }
+}
+
+// @SAFETY: The raw handle behaves as a shared reference into the
+//      component, hence it follows the same rules as a `&C`
+unsafe impl<C: Component + Sync + 'static> Send for ComponentPtr<C> {}
+unsafe impl<C: Component + Sync + 'static> Sync for ComponentPtr<C> {}
+
+///
+/// ### Description
+///
+/// Raw handle into a component which is written into, creating a
+/// [MutComponentHandle] out of a mutable reference to the param and a
+/// [ComponentHandle] otherwise.
+///
+pub struct MutComponentPtr<C: Component + 'static> {
+    component: *mut C,
+    ticks: *mut ComponentTicks,
+    last_run: Tick,
+    this_run: Tick,
+    entity_id: Entity,
+}
+
+impl<C: Component + 'static> MutComponentPtr<C> {
+    pub(crate) fn new(
+        component: *mut C,
+        ticks: *mut ComponentTicks,
+        entity_id: Entity,
+        last_run: Tick,
+        this_run: Tick,
+    ) -> Self {
+        Self {
+            component,
+            ticks,
+            last_run,
+            this_run,
+            entity_id,
+        }
+    }
+}
+
+impl<C: Component + 'static> RawHandle for MutComponentPtr<C> {
+    type Handle<'h> = ComponentHandle<'h, C>;
+    type MutHandle<'h> = MutComponentHandle<'h, C>;
+
+    unsafe fn handle(&self) -> Self::Handle<'_> {
+        ComponentHandle {
+            inner: &*self.component,
+            ticks: &*self.ticks,
+            last_run: self.last_run,
+            this_run: self.this_run,
+            entity_id: self.entity_id,
+        }
+    }
+
+    unsafe fn mut_handle(&mut self) -> Self::MutHandle<'_> {
+        MutComponentHandle {
+            inner: &mut *self.component,
+            ticks: &mut *self.ticks,
+            last_run: self.last_run,
+            this_run: self.this_run,
+            entity_id: self.entity_id,
+        }
+    }
+
+    fn get_component_ptr(&self, component_id: TypeId) -> Option<*const u8> {
+        (component_id == TypeId::of::<C>()).then_some(self.component as *const u8)
+    }
+}
+
+// @SAFETY: The raw handle behaves as a unique reference into the
+//      component (and its ticks), following the same rules as a `&mut C`
+unsafe impl<C: Component + Send + 'static> Send for MutComponentPtr<C> {}
+unsafe impl<C: Component + Sync + 'static> Sync for MutComponentPtr<C> {}
+
+impl RawHandle for Entity {
+    type Handle<'h> = Entity;
+    type MutHandle<'h> = Entity;
+
+    unsafe fn handle(&self) -> Self::Handle<'_> {
+        *self
+    }
+
+    unsafe fn mut_handle(&mut self) -> Self::MutHandle<'_> {
+        *self
+    }
+
+    fn get_component_ptr(&self, _component_id: TypeId) -> Option<*const u8> {
+        None
+    }
+}
+
+impl<R: RawHandle> RawHandle for Option<R> {
+    type Handle<'h> = Option<R::Handle<'h>> where Self: 'h;
+    type MutHandle<'h> = Option<R::MutHandle<'h>> where Self: 'h;
+
+    unsafe fn handle(&self) -> Self::Handle<'_> {
+        self.as_ref().map(|raw| raw.handle())
+    }
+
+    unsafe fn mut_handle(&mut self) -> Self::MutHandle<'_> {
+        self.as_mut().map(|raw| raw.mut_handle())
+    }
+
+    fn get_component_ptr(&self, component_id: TypeId) -> Option<*const u8> {
+        self.as_ref()?.get_component_ptr(component_id)
+    }
+}
+
+macro_rules! raw_handle_tuple {
+    ($($raw: ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($raw: RawHandle),*> RawHandle for ($($raw,)*) {
+            type Handle<'h> = ($($raw::Handle<'h>,)*) where Self: 'h;
+            type MutHandle<'h> = ($($raw::MutHandle<'h>,)*) where Self: 'h;
+
+            unsafe fn handle(&self) -> Self::Handle<'_> {
+                let ($($raw,)*) = self;
+                ($($raw.handle(),)*)
+            }
+
+            unsafe fn mut_handle(&mut self) -> Self::MutHandle<'_> {
+                let ($($raw,)*) = self;
+                ($($raw.mut_handle(),)*)
+            }
+
+            fn get_component_ptr(&self, component_id: TypeId) -> Option<*const u8> {
+                let ($($raw,)*) = self;
+                None$(.or_else(|| $raw.get_component_ptr(component_id)))*
+            }
+        }
+    }
+}
+
+ecs_macros::implement_tuples!(raw_handle_tuple, 0, 21, R);
+
+///
+/// ### Description
+///
+/// Iterator over the handles of a [`system param`](crate::system::param::SystemParam),
+/// borrowing from the param.
+///
+pub struct HandleIter<'a, R: RawHandle> {
+    inner: slice::Iter<'a, R>,
+}
+
+impl<'a, R: RawHandle> HandleIter<'a, R> {
+    /// @SAFETY: The raw handles must belong to a param
+    ///     holding the borrows on their storages
+    pub(crate) fn new(raw_handles: &'a [R]) -> Self {
+        Self {
+            inner: raw_handles.iter(),
+        }
+    }
+}
+
+impl<'a, R: RawHandle> Iterator for HandleIter<'a, R> {
+    type Item = R::Handle<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // @SAFETY: The param holds the borrows for as long as it is borrowed
+        self.inner.next().map(|raw| unsafe { raw.handle() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<R: RawHandle> ExactSizeIterator for HandleIter<'_, R> {}
+
+///
+/// ### Description
+///
+/// Iterator over the mutable handles of a [`system param`](crate::system::param::SystemParam),
+/// mutably borrowing from the param.
+///
+pub struct HandleIterMut<'a, R: RawHandle> {
+    inner: slice::IterMut<'a, R>,
+}
+
+impl<'a, R: RawHandle> HandleIterMut<'a, R> {
+    /// @SAFETY: See [HandleIter::new]
+    pub(crate) fn new(raw_handles: &'a mut [R]) -> Self {
+        Self {
+            inner: raw_handles.iter_mut(),
+        }
+    }
+}
+
+impl<'a, R: RawHandle> Iterator for HandleIterMut<'a, R> {
+    type Item = R::MutHandle<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // @SAFETY: The param holds the borrows for as long as it is borrowed,
+        //      and every raw handle is handed out once
+        self.inner.next().map(|raw| unsafe { raw.mut_handle() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<R: RawHandle> ExactSizeIterator for HandleIterMut<'_, R> {}
+
+
+
+///
+/// ### Description
+///
+/// This is the wrapper struct which allows us to access a
+/// [`component`](Component) tuple acquired using the
 /// [crate::CrossComponentCollectionMut] [`system param`](crate::system::param::SystemParam)
-/// 
-/// This type implements the [Deref] trait to allow us to 
+///
+/// This type implements the [Deref] trait to allow us to
 /// directly access the underlying component.
-/// 
+///
 pub struct ComponentRefHandle<'a,C: Component + 'static> {
-    pub(crate) inner: &'a OwnedRwLockReadGuard<C>,
+    pub(crate) inner: ComponentHandle<'a, C>,
     pub entity_id: Entity,
 }
+impl<C: Component + 'static> ComponentRefHandle<'_, C> {
+    /// See [ComponentHandle::is_added]
+    pub fn is_added(&self) -> bool {
+        self.inner.is_added()
+    }
+
+    /// See [ComponentHandle::is_changed]
+    pub fn is_changed(&self) -> bool {
+        self.inner.is_changed()
+    }
+}
 impl<C: Component + 'static> Deref for ComponentRefHandle<'_, C> {
     type Target = C;
-    
+
     fn deref(&self) -> &Self::Target {
-        self.inner.deref()
+        &self.inner
     }
 }
 
@@ -103,29 +465,39 @@ impl<C: Component + 'static> Deref for ComponentRefHandle<'_, C> {
 
 ///
 /// ### Description
-/// 
-/// This is the wrapper struct which allows us to access a 
-/// [Component] lock tuple acquired using the [crate::CrossComponentCollectionMut]
+///
+/// This is the wrapper struct which allows us to access a
+/// [Component] tuple acquired using the [crate::CrossComponentCollectionMut]
 /// [`system param`](crate::system::param::SystemParam)
-/// 
-/// This type implements the [Deref] and [DerefMut] trait to 
-/// allow us to directly access and modify the underlying 
+///
+/// This type implements the [Deref] and [DerefMut] trait to
+/// allow us to directly access and modify the underlying
 /// component.
-/// 
+///
 pub struct MutComponentRefHandle<'a,C: Component + 'static> {
-    pub(crate) inner: &'a mut OwnedRwLockWriteGuard<C>,
+    pub(crate) inner: MutComponentHandle<'a, C>,
     pub entity_id: Entity,
-    // @TODO: Add the entity_id access here
+}
+impl<C: Component + 'static> MutComponentRefHandle<'_, C> {
+    /// See [ComponentHandle::is_added]
+    pub fn is_added(&self) -> bool {
+        self.inner.is_added()
+    }
+
+    /// See [ComponentHandle::is_changed]
+    pub fn is_changed(&self) -> bool {
+        self.inner.is_changed()
+    }
 }
 impl<C: Component + 'static> Deref for MutComponentRefHandle<'_, C> {
     type Target = C;
-    
+
     fn deref(&self) -> &Self::Target {
-        self.inner.deref()
+        &self.inner
     }
 }
 impl<C: Component + 'static> DerefMut for MutComponentRefHandle<'_, C> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.inner.deref_mut()
+        &mut self.inner
     }
 }
@@ -0,0 +1,155 @@
+use crate::{entity::Entity, world::World};
+
+/// A callback triggered by a lifecycle event of a component,
+/// see [ComponentHooks]
+pub type ComponentHook = fn(&mut World, Entity);
+
+///
+/// ### Description
+///
+/// The lifecycle callbacks of a [`component`](super::Component) type.
+///
+/// - `on_add`: Triggered when the component is attached to an entity
+///   which did not have a component of the type.
+/// - `on_insert`: Triggered every time the component is inserted into an
+///   entity, including when it replaces an existing component of the type.
+///   Runs after `on_add`.
+/// - `on_remove`: Triggered before the component is removed from an
+///   entity, either on its own or along with the entity.
+///
+/// Hooks are declared through the derive macro using
+/// `#[component(on_add = path, on_insert = path, on_remove = path)]`, or
+/// through [`World::register_component_hooks`](World::register_component_hooks).
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// fn on_collider_removed(world: &mut World, entity: Entity) {
+///     // Removing the entity from the spatial index
+/// }
+///
+/// world
+///     .register_component_hooks::<Collider>()
+///     .on_remove(on_collider_removed);
+/// ```
+///
+#[derive(Default, Clone, Copy)]
+pub struct ComponentHooks {
+    pub(crate) on_add: Option<ComponentHook>,
+    pub(crate) on_insert: Option<ComponentHook>,
+    pub(crate) on_remove: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    ///
+    /// ### Description
+    ///
+    /// Sets the hook triggered when the component is added to an entity
+    ///
+    /// WARNING: Setting a hook which is already set will result in a panic
+    pub fn on_add(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(self.on_add.is_none(), "Hook registration failed: Component already has an on_add hook");
+        self.on_add = Some(hook);
+        self
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Sets the hook triggered when the component is inserted into an entity
+    ///
+    /// WARNING: Setting a hook which is already set will result in a panic
+    pub fn on_insert(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(self.on_insert.is_none(), "Hook registration failed: Component already has an on_insert hook");
+        self.on_insert = Some(hook);
+        self
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Sets the hook triggered when the component is removed from an entity
+    ///
+    /// WARNING: Setting a hook which is already set will result in a panic
+    pub fn on_remove(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(self.on_remove.is_none(), "Hook registration failed: Component already has an on_remove hook");
+        self.on_remove = Some(hook);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, sync::mpsc::channel};
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::{component::Component, ecs_base::ECSBase};
+
+    #[derive(Component)]
+    #[component(on_add = on_add, on_insert = on_insert, on_remove = on_remove)]
+    struct Collider;
+
+    thread_local! {
+        /// Hooks triggered on the thread of the test, along with whether
+        /// the component was attached when the hook ran
+        static TRIGGERED: RefCell<Vec<(&'static str, bool)>> = const { RefCell::new(vec![]) };
+    }
+
+    fn record(world: &World, entity_id: Entity, hook: &'static str) {
+        let attached = world.has_component::<Collider>(entity_id);
+        TRIGGERED.with(|triggered| triggered.borrow_mut().push((hook, attached)));
+    }
+
+    fn on_add(world: &mut World, entity_id: Entity) {
+        record(world, entity_id, "on_add");
+    }
+
+    fn on_insert(world: &mut World, entity_id: Entity) {
+        record(world, entity_id, "on_insert");
+    }
+
+    fn on_remove(world: &mut World, entity_id: Entity) {
+        record(world, entity_id, "on_remove");
+    }
+
+    fn take_triggered() -> Vec<(&'static str, bool)> {
+        TRIGGERED.with(|triggered| triggered.take())
+    }
+
+    #[test]
+    fn hooks_are_triggered_in_lifecycle_order() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Collider>();
+
+        let entity_id = world.spawn(Collider);
+        assert_eq!(take_triggered(), [("on_add", true), ("on_insert", true)]);
+
+        // Replacing the component only triggers on_insert
+        world.insert_component(entity_id, Collider);
+        assert_eq!(take_triggered(), [("on_insert", true)]);
+
+        // The component is still attached while on_remove runs
+        world.remove_component_from_entity::<Collider>(entity_id);
+        assert_eq!(take_triggered(), [("on_remove", true)]);
+        assert!(!world.has_component::<Collider>(entity_id));
+
+        world.add_component_to_entity(entity_id, Collider);
+        take_triggered();
+        world.remove_entity(entity_id);
+        assert_eq!(take_triggered(), [("on_remove", true)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Component already has an on_remove hook")]
+    fn hooks_can_not_be_replaced() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Collider>();
+        world.register_component_hooks::<Collider>().on_remove(on_add);
+    }
+}
@@ -1,8 +1,23 @@
+pub mod borrow;
+pub mod bundle;
+pub mod clone;
+pub mod column;
 pub mod component_manager;
+pub mod dynamic;
+pub(crate) mod fetch;
 pub mod handles;
+pub mod hooks;
+pub(crate) mod removal_buffer;
+pub mod required;
+pub mod sparse_set;
+pub mod tick;
 
 use crate::ecs_base::ECSBase;
 
+use clone::ComponentCloneFunction;
+use hooks::ComponentHooks;
+use required::RequiredComponents;
+
 /// ### Description
 /// 
 /// The trait declaration which detects [Component] types
@@ -16,5 +31,92 @@ pub trait Component: ECSBase {
     fn get_name() -> String
     where
         Self: Sized;
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the type of storage used by the world to store
+    /// components of this type. See [StorageType] for more info.
+    ///
+    /// The storage type can be selected through the derive macro
+    /// using the `#[component(storage = "sparse")]` attribute
+    fn storage_type() -> StorageType
+    where
+        Self: Sized,
+    {
+        StorageType::Dense
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Registers the lifecycle [`hooks`](ComponentHooks) of the component
+    /// type, called once when the type is registered in a world.
+    ///
+    /// The hooks can be declared through the derive macro using the
+    /// `#[component(on_add = path, on_insert = path, on_remove = path)]`
+    /// attribute
+    #[allow(unused_variables)]
+    fn register_hooks(hooks: &mut ComponentHooks)
+    where
+        Self: Sized,
+    {
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Records the components which are inserted along with this
+    /// component when it is added to an entity, see [RequiredComponents].
+    ///
+    /// The requirements can be declared through the derive macro using
+    /// the `#[component(requires(Velocity, Mass = default_mass))]` attribute
+    #[allow(unused_variables)]
+    fn register_required_components(required: &mut RequiredComponents)
+    where
+        Self: Sized,
+    {
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the function cloning components of this type in
+    /// [`World::clone_entity`](crate::world::World::clone_entity),
+    /// or None if the component type is not cloneable.
+    ///
+    /// Component types implementing [Clone] can be made cloneable through
+    /// the derive macro using the `#[component(clone)]` attribute
+    fn clone_function() -> Option<ComponentCloneFunction>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+///
+/// ### Description
+///
+/// The type of storage used to store a specific type of [Component]
+/// in a [`world`](crate::world::World)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageType {
+    /// Components are stored in the contiguous columns of the
+    /// [`archetype`](crate::archetype::Archetype) tables.
+    ///
+    /// Iteration over dense components is fast, but adding or removing
+    /// a dense component moves all the components of the entity into
+    /// another archetype table. Suitable for hot iterated data.
+    Dense,
+
+    /// Components are stored in a [`sparse set`](sparse_set::SparseSet)
+    /// outside of the archetype tables.
+    ///
+    /// Adding or removing a sparse component does not move the entity
+    /// across archetypes, at the cost of slower iteration. Suitable for
+    /// components which are added and removed often (status effects, tags)
+    Sparse,
 }
 
@@ -0,0 +1,85 @@
+use std::any::TypeId;
+
+use hashbrown::HashMap;
+
+use super::tick::Tick;
+use crate::entity::Entity;
+
+///
+/// ### Description
+///
+/// Record of the entities which lost a single type of component,
+/// along with the tick at which the component was removed.
+///
+/// The removals are double buffered, following the same rules as the
+/// [`events`](crate::events::event_manager::EventManager): removals
+/// recorded during a frame remain readable until the end of the next
+/// frame, after which they are dropped.
+///
+#[derive(Default)]
+pub(crate) struct RemovedEntities {
+    /// Removals recorded during the previous frame
+    previous: Vec<(Entity, Tick)>,
+
+    /// Removals recorded during the current frame
+    current: Vec<(Entity, Tick)>,
+}
+
+impl RemovedEntities {
+    ///
+    /// ### Description
+    ///
+    /// Returns the entities which lost the component after the
+    /// `last_run` tick of a system running at the `this_run` tick
+    pub fn read(&self, last_run: Tick, this_run: Tick) -> Vec<Entity> {
+        self.previous
+            .iter()
+            .chain(self.current.iter())
+            .filter(|(_, tick)| tick.is_newer_than(last_run, this_run))
+            .map(|(entity_id, _)| *entity_id)
+            .collect()
+    }
+}
+
+///
+/// ### Description
+///
+/// Storage of the component removals of a [`world`](crate::world::World),
+/// read by systems through the
+/// [`RemovedComponents`](crate::system::param::RemovedComponents) parameter.
+///
+pub(crate) struct RemovalBuffer {
+    removals: HashMap<TypeId, RemovedEntities>,
+}
+
+impl RemovalBuffer {
+    pub fn new() -> Self {
+        Self {
+            removals: HashMap::new(),
+        }
+    }
+
+    /// Records that the entity lost its component of the given type at the given tick
+    pub fn record(&mut self, component_id: TypeId, entity_id: Entity, tick: Tick) {
+        self.removals
+            .entry(component_id)
+            .or_default()
+            .current
+            .push((entity_id, tick));
+    }
+
+    pub fn get(&self, component_id: TypeId) -> Option<&RemovedEntities> {
+        self.removals.get(&component_id)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Drops the removals of the previous frame, and keeps the removals
+    /// of the current frame readable for one more frame
+    pub fn refresh_update(&mut self) {
+        for removed_entities in self.removals.values_mut() {
+            removed_entities.previous = std::mem::take(&mut removed_entities.current);
+        }
+    }
+}
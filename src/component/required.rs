@@ -0,0 +1,148 @@
+use std::any::TypeId;
+
+use hashbrown::HashSet;
+
+use super::Component;
+use crate::{entity::Entity, world::World};
+
+/// Type erased insertion of a required component into an entity
+pub type RequiredComponentInsert = Box<dyn Fn(&mut World, Entity) + Send + Sync>;
+
+///
+/// ### Description
+///
+/// Type erased description of a component type required by another
+/// component type, see [RequiredComponents]
+///
+pub struct RequiredComponent {
+    pub(crate) type_id: TypeId,
+    pub(crate) name: String,
+
+    /// Registers the required component type in a world
+    pub(crate) register: fn(&mut World),
+
+    /// Collects the components required by the required component itself
+    pub(crate) collect_required: fn(&mut RequiredComponents),
+
+    /// Attaches a newly constructed component to the entity
+    pub(crate) insert: RequiredComponentInsert,
+}
+
+///
+/// ### Description
+///
+/// The companion components required by a [Component] type.
+///
+/// Adding the component to an entity through any [World] API inserts
+/// every required component missing from the entity, constructed using
+/// the constructor recorded for it. Required components may have
+/// requirements of their own, which are inserted in the same way.
+///
+/// Requirements are declared through the derive macro using the
+/// `#[component(requires(Velocity, Mass = default_mass))]` attribute,
+/// where components without a constructor are constructed with [Default].
+///
+/// WARNING: Registering a component whose requirements form a cycle
+///     will result in a panic
+///
+#[derive(Default)]
+pub struct RequiredComponents {
+    components: Vec<RequiredComponent>,
+}
+
+impl RequiredComponents {
+    pub fn new() -> Self {
+        Self { components: vec![] }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Records the component type [`C`] as required, constructed
+    /// with the given constructor when missing from an entity
+    ///
+    /// WARNING: Requiring the same component type more than once
+    ///     will result in a panic
+    pub fn add<C: Component + 'static>(&mut self, constructor: fn() -> C) {
+        assert!(
+            !self.contains(TypeId::of::<C>()),
+            "Component requirement failed: Duplicate required component [{}] found",
+            C::get_name()
+        );
+
+        self.components.push(RequiredComponent {
+            type_id: TypeId::of::<C>(),
+            name: C::get_name(),
+            register: World::register_component::<C>,
+            collect_required: C::register_required_components,
+            insert: Box::new(move |world, entity_id| {
+                world.add_component_to_entity(entity_id, constructor())
+            }),
+        });
+    }
+
+    pub fn contains(&self, component_id: TypeId) -> bool {
+        self.components
+            .iter()
+            .any(|component| component.type_id == component_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RequiredComponent> {
+        self.components.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Walks the requirement graph starting at the component type with
+    /// the given requirements, and returns the names of the component
+    /// types forming a cycle in the graph, if any.
+    ///
+    /// The returned path starts and ends with the same component name
+    pub(crate) fn find_cycle(root_id: TypeId, root_name: String, required: &Self) -> Option<Vec<String>> {
+        let mut path = vec![(root_id, root_name)];
+        let mut visited = HashSet::new();
+        Self::visit(required, &mut path, &mut visited)
+    }
+
+    fn visit(
+        required: &Self,
+        path: &mut Vec<(TypeId, String)>,
+        visited: &mut HashSet<TypeId>,
+    ) -> Option<Vec<String>> {
+        for component in required.iter() {
+            if let Some(start) = path.iter().position(|(type_id, _)| *type_id == component.type_id) {
+                let mut cycle: Vec<String> = path[start..].iter().map(|(_, name)| name.clone()).collect();
+                cycle.push(component.name.clone());
+                return Some(cycle);
+            }
+
+            // Components whose requirements are fully explored cannot
+            // lead back into the current path
+            if visited.contains(&component.type_id) {
+                continue;
+            }
+
+            let mut nested = RequiredComponents::new();
+            (component.collect_required)(&mut nested);
+
+            path.push((component.type_id, component.name.clone()));
+            let cycle = Self::visit(&nested, path, visited);
+            path.pop();
+
+            if cycle.is_some() {
+                return cycle;
+            }
+            visited.insert(component.type_id);
+        }
+        None
+    }
+}
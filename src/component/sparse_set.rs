@@ -0,0 +1,250 @@
+use std::{cell::UnsafeCell, sync::Arc};
+
+use ecs_macros::ECSBase;
+
+use super::{
+    borrow::BorrowFlag,
+    tick::{ComponentTicks, Tick},
+    Component,
+};
+use crate::{ecs_base::ECSBase, entity::Entity};
+
+///
+/// ### Description
+///
+/// Type erased interface of a [SparseSet] which is exposed to the
+/// [`world`](crate::world::World), used to clean up the sparse
+/// components of an entity without knowing their type.
+///
+pub trait ComponentSparseSet: ECSBase {
+    ///
+    /// ### Description
+    ///
+    /// Removes and drops the component attached to the entity
+    ///
+    /// ### Return Value
+    /// [`false`](bool) if no component was attached to the entity,
+    /// [`true`](bool) otherwise
+    fn remove(&mut self, entity_id: Entity) -> bool;
+
+    fn contains(&self, entity_id: Entity) -> bool;
+
+    /// Returns the entities which have a component in the set
+    fn entities(&self) -> &[Entity];
+}
+
+///
+/// ### Description
+///
+/// Storage for components using the [`sparse`](super::StorageType::Sparse)
+/// storage type.
+///
+/// The components are packed in a dense vector, while a sparse vector
+/// indexed by the [`entity index`](Entity::index) maps each entity to
+/// its component in the dense vector. This makes insertion, removal and
+/// lookup O(1) without moving the entity across archetype tables.
+///
+/// #### SAFETY:
+/// Just like a [`Column`](super::column::Column), the components are not
+/// locked individually, and must only be accessed while the set is borrowed
+/// through its [`borrow flag`](SparseSet::borrow_flag)
+///
+#[derive(ECSBase)]
+pub(crate) struct SparseSet<C: Component + 'static> {
+    /// Densely packed components
+    components: Vec<UnsafeCell<C>>,
+
+    /// Change ticks of the components, in the same order as the
+    /// [`components`](SparseSet::components) vector
+    ticks: Vec<UnsafeCell<ComponentTicks>>,
+
+    /// Entities owning the components, in the same order as the
+    /// [`components`](SparseSet::components) vector
+    entities: Vec<Entity>,
+
+    /// Entity index to dense index mapping
+    sparse: Vec<Option<usize>>,
+
+    /// Set level borrow tracking, replacing per-component locks
+    borrow_flag: Arc<BorrowFlag>,
+}
+
+impl<C: Component + 'static> SparseSet<C> {
+    pub fn new() -> Self {
+        Self {
+            components: vec![],
+            ticks: vec![],
+            entities: vec![],
+            sparse: vec![],
+            borrow_flag: BorrowFlag::new(),
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Attaches the component to the entity, recording the given
+    /// tick as the tick at which it was added
+    ///
+    /// WARNING: Adding a component to an entity which already has a component
+    ///     in the set will result in a panic
+    pub fn insert(&mut self, entity_id: Entity, component: C, tick: Tick) {
+        assert!(
+            !self.contains(entity_id),
+            "Component addition to Entity [{:?}] failed: Duplicate components are not allowed in entities.",
+            entity_id
+        );
+
+        let sparse_index = entity_id.index() as usize;
+        if sparse_index >= self.sparse.len() {
+            self.sparse.resize(sparse_index + 1, None);
+        }
+
+        self.sparse[sparse_index] = Some(self.components.len());
+        self.components.push(UnsafeCell::new(component));
+        self.ticks.push(UnsafeCell::new(ComponentTicks::new(tick)));
+        self.entities.push(entity_id);
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Replaces the component attached to the entity, recording the
+    /// given tick as the tick at which it was changed
+    ///
+    /// WARNING: Replacing the component of an entity which does not have
+    ///     a component in the set will result in a panic
+    pub fn replace(&mut self, entity_id: Entity, component: C, tick: Tick) {
+        let dense_index = self.get_dense_index(entity_id).unwrap_or_else(|| {
+            panic!("Component replacement failed: Entity [{:?}] does not have the component", entity_id)
+        });
+
+        *self.components[dense_index].get_mut() = component;
+        self.ticks[dense_index].get_mut().set_changed(tick);
+    }
+
+    pub fn borrow_flag(&self) -> &Arc<BorrowFlag> {
+        &self.borrow_flag
+    }
+
+    /// Returns the entities which have a component in the set
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns a pointer to the component attached to the entity,
+    /// or None if the entity does not have a component in the set.
+    ///
+    /// The pointer may only be dereferenced mutably while the set is
+    /// borrowed mutably, and immutably while the set is borrowed.
+    pub fn get_ptr(&self, entity_id: Entity) -> Option<*mut C> {
+        let dense_index = self.get_dense_index(entity_id)?;
+        Some(self.components[dense_index].get())
+    }
+
+    /// Returns a pointer to the change ticks of the component attached
+    /// to the entity. See [SparseSet::get_ptr] for the access rules.
+    pub fn get_ticks_ptr(&self, entity_id: Entity) -> Option<*mut ComponentTicks> {
+        let dense_index = self.get_dense_index(entity_id)?;
+        Some(self.ticks[dense_index].get())
+    }
+}
+
+impl<C: Component + 'static> ComponentSparseSet for SparseSet<C> {
+    fn remove(&mut self, entity_id: Entity) -> bool {
+        let dense_index = match self.get_dense_index(entity_id) {
+            Some(dense_index) => dense_index,
+            None => return false,
+        };
+
+        // O(1) removal time, the removed element is replaced
+        // by the last element in the dense vectors
+        self.components.swap_remove(dense_index);
+        self.ticks.swap_remove(dense_index);
+        self.entities.swap_remove(dense_index);
+        self.sparse[entity_id.index() as usize] = None;
+
+        // Updating the lookup of the moved element
+        if dense_index < self.entities.len() {
+            let moved_entity = self.entities[dense_index];
+            self.sparse[moved_entity.index() as usize] = Some(dense_index);
+        }
+
+        true
+    }
+
+    fn contains(&self, entity_id: Entity) -> bool {
+        self.get_dense_index(entity_id).is_some()
+    }
+
+    fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+}
+
+/// Private methods for [SparseSet]
+impl<C: Component + 'static> SparseSet<C> {
+    /// Finds the index of the component attached to the entity
+    /// in the dense vectors.
+    ///
+    /// The generation of the stored entity is verified to ensure that stale
+    /// entity ids sharing the same index do not access the component
+    fn get_dense_index(&self, entity_id: Entity) -> Option<usize> {
+        let dense_index = (*self.sparse.get(entity_id.index() as usize)?)?;
+
+        if self.entities[dense_index] != entity_id {
+            return None;
+        }
+        Some(dense_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ecs_macros::Component;
+
+    use super::*;
+
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "sparse")]
+    struct Stunned(u32);
+
+    const TICK: Tick = Tick::new(1);
+
+    fn get(set: &SparseSet<Stunned>, entity_id: Entity) -> Option<&Stunned> {
+        // @SAFETY: The set is borrowed for as long as the reference is used
+        set.get_ptr(entity_id).map(|ptr| unsafe { &*ptr })
+    }
+
+    #[test]
+    fn removal_keeps_the_remaining_components() {
+        let mut set = SparseSet::new();
+        let entities: Vec<Entity> = (0..3).map(|index| Entity::from_bits(index << 32 | 1)).collect();
+        for (value, entity_id) in entities.iter().enumerate() {
+            set.insert(*entity_id, Stunned(value as u32), TICK);
+        }
+
+        assert!(set.remove(entities[0]));
+        assert!(!set.remove(entities[0]));
+        assert_eq!(get(&set, entities[0]), None);
+        assert_eq!(get(&set, entities[1]), Some(&Stunned(1)));
+        assert_eq!(get(&set, entities[2]), Some(&Stunned(2)));
+        assert_eq!(ComponentSparseSet::entities(&set).len(), 2);
+    }
+
+    #[test]
+    fn stale_entities_do_not_access_the_component() {
+        let mut set = SparseSet::new();
+        let entity_id = Entity::from_bits(4 << 32 | 2);
+        let stale = Entity::from_bits(4 << 32 | 1);
+        set.insert(entity_id, Stunned(1), TICK);
+
+        assert!(set.contains(entity_id));
+        assert!(!set.contains(stale));
+        assert_eq!(get(&set, stale), None);
+        assert!(!set.remove(stale));
+    }
+}
@@ -0,0 +1,77 @@
+///
+/// ### Description
+///
+/// A point in time of a [`world`](crate::world::World), measured by the
+/// change tick of the world.
+///
+/// The change tick of the world advances every time a system runs, and
+/// components record the tick at which they were added or last mutated.
+/// Comparing these against the ticks of a system's runs tells whether a
+/// component was added or changed since the system last ran.
+///
+/// The tick wraps around on overflow, hence comparisons are only valid
+/// as long as a system does not miss more than [u32::MAX] ticks between runs.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tick(u32);
+
+impl Tick {
+    pub const fn new(tick: u32) -> Self {
+        Self(tick)
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns true if this tick occurred after the `last_run` tick of a
+    /// system, while the system is running at the `this_run` tick.
+    ///
+    /// The comparison is done relative to `this_run` to stay correct
+    /// across a wrap around of the world change tick
+    pub fn is_newer_than(&self, last_run: Tick, this_run: Tick) -> bool {
+        let ticks_since_self = this_run.0.wrapping_sub(self.0);
+        let ticks_since_last_run = this_run.0.wrapping_sub(last_run.0);
+
+        ticks_since_last_run > ticks_since_self
+    }
+}
+
+///
+/// ### Description
+///
+/// The change ticks of a single component, stored along with the
+/// component in its storage
+///
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentTicks {
+    /// Tick at which the component was attached to its entity
+    pub(crate) added: Tick,
+
+    /// Tick at which the component was last mutated (or added)
+    pub(crate) changed: Tick,
+}
+
+impl ComponentTicks {
+    pub(crate) fn new(tick: Tick) -> Self {
+        Self {
+            added: tick,
+            changed: tick,
+        }
+    }
+
+    pub fn is_added(&self, last_run: Tick, this_run: Tick) -> bool {
+        self.added.is_newer_than(last_run, this_run)
+    }
+
+    pub fn is_changed(&self, last_run: Tick, this_run: Tick) -> bool {
+        self.changed.is_newer_than(last_run, this_run)
+    }
+
+    pub(crate) fn set_changed(&mut self, tick: Tick) {
+        self.changed = tick;
+    }
+}
@@ -10,3 +10,89 @@ pub trait ECSBase {
         TypeId::of::<Self>()
     }
 }
+
+///
+/// ### Description
+///
+/// Returns the name of the type without the module paths, keeping the
+/// type parameters of generic types. (`Team<Red>` instead of
+/// `my_game::components::Team<my_game::teams::Red>`)
+///
+pub fn short_type_name<T: ?Sized>() -> String {
+    let full_name = std::any::type_name::<T>();
+    let mut short_name = String::with_capacity(full_name.len());
+    let mut segment = String::new();
+
+    for c in full_name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+            continue;
+        }
+
+        short_name.push_str(segment.rsplit("::").next().unwrap_or_default());
+        segment.clear();
+        short_name.push(c);
+    }
+    short_name.push_str(segment.rsplit("::").next().unwrap_or_default());
+    short_name
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+
+    use ecs_macros::{Component, Event, Resource, SystemParam};
+
+    use super::*;
+    use crate::{component::Component, events::Event, resource::Resource};
+
+    struct Red;
+
+    #[derive(Component)]
+    struct Team<T: 'static> {
+        _marker: PhantomData<T>,
+    }
+
+    #[derive(Component)]
+    struct Grid<const N: usize>;
+
+    #[derive(Resource)]
+    struct Score<T: 'static> {
+        _marker: PhantomData<T>,
+    }
+
+    #[derive(Event)]
+    struct Scored<T: 'static> {
+        _marker: PhantomData<T>,
+    }
+
+    #[derive(SystemParam)]
+    struct Scoreboard<T: 'static> {
+        _marker: PhantomData<T>,
+    }
+
+    fn assert_resource<R: Resource + 'static>() {}
+
+    fn assert_event<E: Event + 'static>() {}
+
+    fn assert_base<B: ECSBase + 'static>() {}
+
+    #[test]
+    fn short_type_name_strips_module_paths() {
+        assert_eq!(short_type_name::<u32>(), "u32");
+        assert_eq!(short_type_name::<Red>(), "Red");
+        assert_eq!(short_type_name::<Vec<Option<String>>>(), "Vec<Option<String>>");
+        assert_eq!(short_type_name::<(u32, &Red)>(), "(u32, &Red)");
+    }
+
+    #[test]
+    fn generic_derives_name_the_concrete_type() {
+        assert_eq!(Team::<Red>::get_name(), "Team<Red>");
+        assert_eq!(Team::<Team<u8>>::get_name(), "Team<Team<u8>>");
+        assert_eq!(Grid::<3>::get_name(), "Grid<3>");
+
+        assert_resource::<Score<Red>>();
+        assert_event::<Scored<Red>>();
+        assert_base::<Scoreboard<Red>>();
+    }
+}
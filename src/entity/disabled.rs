@@ -0,0 +1,85 @@
+use crate::{component::Component, ecs_base::ECSBase};
+use ecs_macros::Component;
+
+///
+/// ### Description
+///
+/// Marker component of the entities which are switched off, through
+/// [`World::disable`](crate::world::World::disable).
+///
+/// Disabled entities keep all of their components, but are skipped by
+/// the [`Query`](crate::system::param::Query),
+/// [`QueryMut`](crate::system::param::QueryMut) and component collection
+/// parameters. A query opts into the disabled entities through the
+/// [`IncludeDisabled`](crate::system::param::IncludeDisabled) filter.
+///
+/// The component uses the [`dense`](crate::component::StorageType::Dense)
+/// storage, hence the disabled entities live in separate archetypes which
+/// the queries skip as a whole.
+///
+#[derive(Component)]
+pub struct Disabled;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use super::*;
+    use crate::{
+        entity::Entity,
+        system::{
+            param::{ComponentCollection, IncludeDisabled, Query, QueryFilter, SystemParam},
+            state::SystemState,
+        },
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct Bullet;
+
+    fn new_world() -> (World, Entity, Entity) {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Bullet>();
+
+        let active = world.spawn(Bullet);
+        let pooled = world.spawn(Bullet);
+        world.disable(pooled);
+        (world, active, pooled)
+    }
+
+    fn query_entities<F: QueryFilter + 'static>(world: &World) -> Vec<Entity> {
+        let mut state = SystemState::new();
+        state.begin_run(world.increment_change_tick());
+        let (_, query) = Query::<(Entity, &Bullet), F>::initialise(world, &mut state);
+        let mut entities: Vec<Entity> = query
+            .map(|query| query.iter().map(|(entity_id, _)| entity_id).collect())
+            .unwrap_or_default();
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn disabled_entities_are_skipped_by_default() {
+        let (world, active, pooled) = new_world();
+
+        assert!(world.is_disabled(pooled) && !world.is_disabled(active));
+        assert!(world.has_component::<Bullet>(pooled));
+        assert_eq!(query_entities::<()>(&world), [active]);
+        assert_eq!(query_entities::<IncludeDisabled>(&world), [active, pooled]);
+
+        let mut state = SystemState::new();
+        state.begin_run(world.increment_change_tick());
+        let (_, collection) = ComponentCollection::<Bullet>::initialise(&world, &mut state);
+        assert_eq!(collection.unwrap().iter().count(), 1);
+    }
+
+    #[test]
+    fn enabled_entities_are_matched_again() {
+        let (mut world, active, pooled) = new_world();
+        world.enable(pooled);
+
+        assert!(!world.is_disabled(pooled));
+        assert_eq!(query_entities::<()>(&world), [active, pooled]);
+    }
+}
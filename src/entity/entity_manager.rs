@@ -1,5 +1,23 @@
+use std::{
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicIsize, Ordering},
+        Arc, RwLock,
+    },
+};
+
 use super::Entity;
 
+/// State of a single entity index in the [EntityManager]
+#[derive(Clone, Copy)]
+struct EntitySlot {
+    /// Generation of the entity occupying the slot, or of the next
+    /// entity to occupy the slot if the slot is free
+    generation: NonZeroU32,
+
+    is_alive: bool,
+}
+
 ///
 /// ### Description
 /// 
@@ -10,86 +28,214 @@ use super::Entity;
 ///     3. Implementing an event emission system to enable events.
 ///
 ///
-
 pub(crate) struct EntityManager {
-    /// Store for Entities
-    entities: Vec<Entity>,
+    /// Store for the entity slots, indexed by the entity index
+    slots: Vec<EntitySlot>,
 
-    /// Empty indexes
-    empty_index: Vec<usize>,
-    
+    /// Free list of the slots which can be reused, along with the
+    /// reservations made out of it
+    reserver: Arc<EntityReserver>,
 }
 
 impl EntityManager {
+    /// Marks the slot of the entity as occupied by the entity
+    fn occupy_slot(&mut self, free_list: &mut FreeList, entity_id: Entity) {
+        let slot = EntitySlot {
+            generation: entity_id.generation,
+            is_alive: true,
+        };
 
+        let index = entity_id.index as usize;
+        if index == self.slots.len() {
+            self.slots.push(slot);
+        } else {
+            self.slots[index] = slot;
+        }
+        free_list.slot_count = self.slots.len();
+    }
+}
 
-    #[doc(hidden)]
+///
+/// ### Description
+///
+/// Free list of the [EntityManager], from which
+/// [`entity ids`](crate::entity::Entity) are reserved.
+///
+/// The reserver is shared with the [`command buffer writers`](crate::system::param::CommandBufferWriter)
+/// of the systems, letting them reserve ids while the world is shared.
+/// The free list is only modified by the manager, which requires mutable
+/// access to the world.
+///
+pub(crate) struct EntityReserver {
+    free_list: RwLock<FreeList>,
+
+    /// Number of free entities which have not been reserved yet.
+    ///
+    /// Reservations decrement the cursor atomically. Once the free entities
+    /// run out, the cursor goes negative and each further reservation claims
+    /// a new index past the end of the entity store. The reservations are
+    /// materialised (and the cursor reset) by [EntityManager::flush]
+    free_cursor: AtomicIsize,
+}
+
+/// Free slots of the [EntityManager]
+struct FreeList {
+    /// Ids of the next entities of the free slots, reused from the end
+    ///
+    /// @NOTE: Slots whose generation is exhausted are retired, and
+    ///     never return to the free list
+    entities: Vec<Entity>,
+
+    /// Number of slots of the entity store
+    slot_count: usize,
+}
+
+impl EntityReserver {
+    fn new() -> Self {
+        Self {
+            free_list: RwLock::new(FreeList {
+                entities: vec![],
+                slot_count: 0,
+            }),
+            free_cursor: AtomicIsize::new(0),
+        }
+    }
+
+    ///
     /// ### Description
-    /// 
-    /// Generates and [`entity id`](crate::entity::Entity)
-    /// based on the previous generation of an empty index.
-    /// 
-    fn generate_entity_id(&mut self) -> Entity {
-        // If we have a whole, we fill it, else we create a new position
-        if !self.empty_index.is_empty() {
-            let index = self.empty_index.pop().unwrap();
-            
-            Entity {
-                index: index as u32,
-                generation: self.entities[index].generation + 1,
-            }
+    ///
+    /// Reserves an [`entity id`](crate::entity::Entity), allowing ids to be
+    /// reserved from multiple threads at once.
+    ///
+    /// The reserved id is only materialised as a live entity once
+    /// [EntityManager::flush] is called
+    ///
+    pub fn reserve_entity(&self) -> Entity {
+        let free_list = self.free_list.read().unwrap();
+        let cursor = self.free_cursor.fetch_sub(1, Ordering::Relaxed);
+
+        if cursor > 0 {
+            // Reusing the free entities from the end, in the same order as
+            // they would be popped by the manager
+            free_list.entities[cursor as usize - 1]
         } else {
             Entity {
-                index: self.entities.len() as u32,
-                generation: 0,
+                index: (free_list.slot_count as isize - cursor) as u32,
+                generation: NonZeroU32::MIN,
             }
         }
     }
+
+    /// Modifies the free list, resetting the cursor to the free entities
+    fn modify<R>(&self, f: impl FnOnce(&mut FreeList, isize) -> R) -> R {
+        let mut free_list = self.free_list.write().unwrap();
+        let result = f(&mut free_list, self.free_cursor.load(Ordering::Relaxed));
+
+        self.free_cursor.store(free_list.entities.len() as isize, Ordering::Relaxed);
+        result
+    }
 }
 
 /// Public member implementations
 impl EntityManager {
     pub fn new() -> Self {
         Self {
-            entities: vec![],
-            empty_index: vec![],
+            slots: vec![],
+            reserver: Arc::new(EntityReserver::new()),
         }
     }
 
-
-    /// 
-    /// ### Description
-    /// 
-    /// Returns the vector of all active entities living 
-    /// in the world entity manager.
-    /// 
-    pub fn get_active_entities(&self) -> hashbrown::HashSet<&Entity> {
-        let valid_entities: hashbrown::HashSet<&Entity> = self.entities.iter().filter(|e| {
-            !self.empty_index.contains(&(e.index as usize))
-        }).collect();
-
-        valid_entities
+    /// Returns the [EntityReserver] of the manager
+    pub fn reserver(&self) -> Arc<EntityReserver> {
+        self.reserver.clone()
     }
 
+
     ///
     /// ### Description
     /// 
-    /// Creates an Entity and gives it's [`EntityId`][crate::entity::Entity]
+    /// Creates an Entity and gives it's [`EntityId`][crate::entity::Entity],
+    /// reusing a free slot with its next generation if one is available.
     ///
     /// Returns an [`EntityId`][crate::entity::Entity], which must be used to perform
     /// all further operations on the entity
     ///
+    /// WARNING: Creating an entity while reservations are pending
+    ///     will result in a panic. See [EntityManager::flush]
+    ///
     pub fn create_entity(&mut self) -> Entity {
-        let entity_id = self.generate_entity_id();
-        let entity_index = entity_id.index;
+        self.verify_flushed("Entity creation");
 
-        if entity_index == self.entities.len() as u32 {
-            self.entities.push(entity_id);
-        } else {
-            self.entities[entity_index as usize] = entity_id;
-        }
+        let reserver = self.reserver.clone();
+        reserver.modify(|free_list, _| {
+            // If we have a whole, we fill it, else we create a new position
+            let entity_id = free_list.entities.pop().unwrap_or(Entity {
+                index: self.slots.len() as u32,
+                generation: NonZeroU32::MIN,
+            });
+            self.occupy_slot(free_list, entity_id);
+            entity_id
+        })
+    }
 
-        entity_id
+    ///
+    /// ### Description
+    ///
+    /// Reserves an [`entity id`](crate::entity::Entity) without mutable
+    /// access to the manager. See [EntityReserver::reserve_entity]
+    ///
+    pub fn reserve_entity(&self) -> Entity {
+        self.reserver.reserve_entity()
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Materialises the reserved entity ids, calling `init` with each of them
+    ///
+    pub fn flush(&mut self, mut init: impl FnMut(Entity)) {
+        let reserver = self.reserver.clone();
+        reserver.modify(|free_list, cursor| {
+            // Reservations past the end of the entity store
+            for _ in 0..(-cursor).max(0) {
+                let entity_id = Entity {
+                    index: self.slots.len() as u32,
+                    generation: NonZeroU32::MIN,
+                };
+                self.occupy_slot(free_list, entity_id);
+                init(entity_id);
+            }
+
+            // Reservations of free entities
+            let reserved_from = cursor.max(0) as usize;
+            let reserved: Vec<Entity> = free_list.entities.drain(reserved_from..).collect();
+            for entity_id in reserved {
+                self.occupy_slot(free_list, entity_id);
+                init(entity_id);
+            }
+        });
+    }
+
+    /// Returns true if no reserved entity id is waiting to be materialised
+    pub fn is_flushed(&self) -> bool {
+        let free_list = self.reserver.free_list.read().unwrap();
+        self.reserver.free_cursor.load(Ordering::Relaxed) == free_list.entities.len() as isize
+    }
+
+    fn verify_flushed(&self, operation: &str) {
+        assert!(self.is_flushed(), "{operation} failed: Reserved entities must be flushed first");
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns true if the entity exists, false if the entity was never
+    /// created, has been disposed, or is only reserved
+    ///
+    pub fn is_alive(&self, entity_id: Entity) -> bool {
+        self.slots
+            .get(entity_id.index as usize)
+            .is_some_and(|slot| slot.is_alive && slot.generation == entity_id.generation)
     }
 
     ///
@@ -101,13 +247,143 @@ impl EntityManager {
     /// invalidated and any future operations on the entity will result in 
     /// no operation being performed, and an error log will be generated
     ///
+    /// The slot of the entity is reused with the next generation. A slot
+    /// whose generation is exhausted is retired instead, since reusing it
+    /// would revive the stale ids of the slot
+    ///
+    /// WARNING: Disposing an entity while reservations are pending
+    ///     will result in a panic. See [EntityManager::flush]
+    ///
     pub fn dispose_entity_id(&mut self, entity_id: Entity) {
-        if self.entities[entity_id.index as usize].generation == entity_id.generation {
+        self.verify_flushed("Entity disposal");
+
+        if !self.is_alive(entity_id) {
             let err_str = format!("Failed to dispose entity id {:?}: ID does not exist in the system anymore. It might have been deleted previously", entity_id);
             log::warn!("{err_str}");
             return;
         }
 
-        self.empty_index.push(entity_id.index as usize);
+        let index = entity_id.index as usize;
+        let slot = &mut self.slots[index];
+        slot.is_alive = false;
+
+        // Slots with exhausted generations are retired by leaving them out of the free list
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.reserver.modify(|free_list, _| {
+                free_list.entities.push(Entity {
+                    index: index as u32,
+                    generation,
+                })
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flush(manager: &mut EntityManager) -> Vec<Entity> {
+        let mut flushed = vec![];
+        manager.flush(|entity_id| flushed.push(entity_id));
+        flushed
+    }
+
+    #[test]
+    fn reserved_entities_are_alive_after_flush() {
+        let mut manager = EntityManager::new();
+        let created = manager.create_entity();
+
+        let reserver = manager.reserver();
+        let first = reserver.reserve_entity();
+        let second = manager.reserve_entity();
+        assert_ne!(first, second);
+        assert!(!manager.is_alive(first) && !manager.is_flushed());
+
+        let mut flushed = flush(&mut manager);
+        flushed.sort_by_key(|entity_id| entity_id.index);
+        assert_eq!(flushed, [first, second]);
+        assert!(manager.is_flushed());
+        assert!([created, first, second].iter().all(|entity_id| manager.is_alive(*entity_id)));
+    }
+
+    #[test]
+    fn reserved_entities_reuse_free_slots_with_next_generation() {
+        let mut manager = EntityManager::new();
+        let disposed = manager.create_entity();
+        let kept = manager.create_entity();
+        manager.dispose_entity_id(disposed);
+
+        let reused = manager.reserve_entity();
+        let appended = manager.reserve_entity();
+        assert_eq!(reused.index, disposed.index);
+        assert_eq!(reused.generation.get(), disposed.generation.get() + 1);
+        assert_eq!(appended.index, kept.index + 1);
+        assert_eq!(appended.generation, NonZeroU32::MIN);
+
+        assert_eq!(flush(&mut manager).len(), 2);
+        assert!(manager.is_alive(reused) && manager.is_alive(appended));
+        assert!(!manager.is_alive(disposed));
+
+        // The next entity does not reuse the materialised reservations
+        let created = manager.create_entity();
+        assert_eq!(created.index, appended.index + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Reserved entities must be flushed first")]
+    fn creating_entities_with_pending_reservations_panics() {
+        let mut manager = EntityManager::new();
+        manager.reserve_entity();
+        manager.create_entity();
+    }
+
+    #[test]
+    fn disposing_entities_with_pending_reservations_keeps_the_reservations() {
+        let mut manager = EntityManager::new();
+        let disposed = manager.create_entity();
+        let reserved = manager.reserve_entity();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            manager.dispose_entity_id(disposed)
+        }));
+        assert!(result.is_err());
+        assert!(manager.is_alive(disposed));
+
+        assert_eq!(flush(&mut manager), [reserved]);
+        manager.dispose_entity_id(disposed);
+        assert!(manager.is_alive(reserved) && !manager.is_alive(disposed));
+    }
+
+    #[test]
+    fn stale_entity_ids_are_not_alive() {
+        let mut manager = EntityManager::new();
+        let stale = manager.create_entity();
+        manager.dispose_entity_id(stale);
+        assert!(!manager.is_alive(stale));
+
+        let reused = manager.create_entity();
+        assert_eq!(reused.index, stale.index);
+        assert!(manager.is_alive(reused) && !manager.is_alive(stale));
+
+        // Disposing the stale id leaves the new occupant of the slot alive
+        manager.dispose_entity_id(stale);
+        assert!(manager.is_alive(reused));
+    }
+
+    #[test]
+    fn slots_with_exhausted_generations_are_retired() {
+        let mut manager = EntityManager::new();
+        let entity_id = manager.create_entity();
+        manager.slots[entity_id.index as usize].generation = NonZeroU32::MAX;
+        let last = Entity {
+            index: entity_id.index,
+            generation: NonZeroU32::MAX,
+        };
+
+        manager.dispose_entity_id(last);
+        assert!(!manager.is_alive(last));
+        assert_ne!(manager.create_entity().index, last.index);
     }
 }
@@ -1,6 +1,12 @@
+pub mod disabled;
 pub mod entity_manager;
 
-use std::{fmt::Debug, hash::Hash};
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    hash::Hash,
+    num::NonZeroU32,
+};
 
 
 
@@ -9,42 +15,169 @@ use std::{fmt::Debug, hash::Hash};
 /// a series of bits. (This is the explanation given in the bevy source code,
 /// but they have a function to bits for the entity id)
 /// 
+/// The generation of an entity is never zero, which lets an
+/// [`Option<Entity>`](Option) use the same 8 bytes as the entity itself.
+/// 
 /// For more on generational id, see the reference in notes.md
 #[repr(C, align(8))]
 #[derive(Debug)]
 pub struct Entity {
     index: u32,
-    generation: u32
+    generation: NonZeroU32
 }
 
 impl Entity {
-    // /// Supposed to be useful for optimizing codegen for things like
-    // /// PartialOrd le, gt, ge operators.
-    // /// But since I do not understand this at this point in time(10th march 2023),
-    // /// I will not proceed with to_bits approach for the moment
-    // /// Once I completely understand the nuances, I will proceed with the approach
-    // /// mentioned in the bevy_ecs code.
-    // pub fn to_bits(&self) -> u64 {
-    //     (self.index as u64) << 32 | (self.generation as u64)
-    // }
+    ///
+    /// ### Description
+    ///
+    /// An entity id which never refers to a live entity, meant to fill
+    /// entity fields and arrays before the real ids are known.
+    ///
+    /// @NOTE: Every operation performed on the placeholder through the
+    ///     [`world`](crate::world::World) is ignored
+    pub const PLACEHOLDER: Entity = Entity {
+        index: u32::MAX,
+        generation: NonZeroU32::MIN,
+    };
+
+    /// Returns the index of the entity slot in the entity manager
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the generation of the entity slot this entity was created in,
+    /// starting from 1
+    pub fn generation(&self) -> u32 {
+        self.generation.get()
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Packs the entity id into 64 bits, with the index in the upper half
+    /// and the generation in the lower half. Hence the bits of two entities
+    /// are ordered in the same way as the entities themselves.
+    ///
+    /// The entity can be recovered using [Entity::from_bits]
+    pub fn to_bits(&self) -> u64 {
+        (self.index as u64) << 32 | (self.generation.get() as u64)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Reconstructs an entity id packed using [Entity::to_bits]
+    ///
+    /// WARNING: Calling this function with bits which were not produced
+    ///     by [Entity::to_bits] (i.e. with a zero generation) will result
+    ///     in a panic. See [Entity::try_from_bits]
+    pub fn from_bits(bits: u64) -> Entity {
+        Self::try_from_bits(bits).expect("Entity conversion failed: Invalid entity bits, the generation is zero")
+    }
+
+    /// Reconstructs an entity id packed using [Entity::to_bits],
+    /// or returns None if the bits hold a zero generation
+    pub fn try_from_bits(bits: u64) -> Option<Entity> {
+        Some(Entity {
+            index: (bits >> 32) as u32,
+            generation: NonZeroU32::new(bits as u32)?,
+        })
+    }
 }
 impl Clone for Entity{
     fn clone(&self) -> Self {
-        Self { index: self.index.clone(), generation: self.generation.clone() }
+        *self
     }
 }
 impl Copy for Entity {}
 
 impl PartialEq for Entity {
     fn eq(&self, other: &Self) -> bool {
-        self.index == other.index && self.generation == other.generation
+        self.to_bits() == other.to_bits()
     }
 }
 impl Eq for Entity {}
 
+/// Entities are ordered by their index, then by their generation
+impl PartialOrd for Entity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Entity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_bits().cmp(&other.to_bits())
+    }
+}
+
 impl Hash for Entity{
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.index.hash(state);
-        self.generation.hash(state);
+        self.to_bits().hash(state);
+    }
+}
+
+/// Formats the entity as `{index}v{generation}`, e.g. `12v3`
+impl Display for Entity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use crate::world::World;
+
+    use super::*;
+
+    fn entity(index: u32, generation: u32) -> Entity {
+        Entity {
+            index,
+            generation: NonZeroU32::new(generation).unwrap(),
+        }
+    }
+
+    #[test]
+    fn optional_entity_uses_the_generation_niche() {
+        assert_eq!(std::mem::size_of::<Entity>(), 8);
+        assert_eq!(std::mem::size_of::<Option<Entity>>(), 8);
+    }
+
+    #[test]
+    fn bits_round_trip() {
+        let entity_id = entity(12, 3);
+        assert_eq!(entity_id.to_bits(), 12 << 32 | 3);
+        assert_eq!(Entity::from_bits(entity_id.to_bits()), entity_id);
+        assert_eq!(Entity::from_bits(Entity::PLACEHOLDER.to_bits()), Entity::PLACEHOLDER);
+
+        // A zero generation is never produced by to_bits
+        assert_eq!(Entity::try_from_bits(12 << 32), None);
+    }
+
+    #[test]
+    fn entities_are_ordered_by_index_then_generation() {
+        let mut entities = vec![entity(2, 1), entity(1, 5), entity(1, 2), Entity::PLACEHOLDER];
+        entities.sort();
+        assert_eq!(entities, [entity(1, 2), entity(1, 5), entity(2, 1), Entity::PLACEHOLDER]);
+    }
+
+    #[test]
+    fn entities_are_displayed_with_their_generation() {
+        assert_eq!(entity(12, 3).to_string(), "12v3");
+        assert_eq!(format!("{}", entity(0, 1)), "0v1");
+    }
+
+    #[test]
+    fn placeholder_is_never_alive() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        for _ in 0..4 {
+            world.spawn(());
+        }
+
+        assert!(!world.is_alive(Entity::PLACEHOLDER));
+        world.remove_entity(Entity::PLACEHOLDER);
+        assert!(!world.is_alive(Entity::PLACEHOLDER));
+    }
+}
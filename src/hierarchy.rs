@@ -0,0 +1,269 @@
+use std::{any::TypeId, collections::VecDeque};
+
+use crate::{
+    component::Component,
+    ecs_base::ECSBase,
+    entity::Entity,
+    system::param::{base_query::SystemQuery, Query, QueryFilter, QueryMut},
+    world::World,
+};
+use ecs_macros::Component;
+
+///
+/// ### Description
+///
+/// Component holding the parent of an entity in the entity hierarchy.
+///
+/// The component is maintained by the [World] hierarchy APIs
+/// ([World::set_parent], [World::add_child] and [World::remove_parent])
+/// along with the [Children] component of the parent, and must not be
+/// attached to entities directly.
+///
+/// Removing the component (or the entity) detaches the entity from
+/// the [Children] of its parent.
+///
+#[derive(Component)]
+#[component(on_remove = on_parent_removed)]
+pub struct Parent(pub(crate) Entity);
+
+impl Parent {
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+///
+/// ### Description
+///
+/// Component holding the children of an entity in the entity hierarchy.
+/// See [Parent] for the maintenance rules of the hierarchy.
+///
+/// Removing the component (or the entity) removes the [Parent]
+/// component of all the children, leaving them as root entities.
+/// Use [World::despawn_recursive] to remove the children along
+/// with the entity.
+///
+#[derive(Component)]
+#[component(on_remove = on_children_removed)]
+pub struct Children(pub(crate) Vec<Entity>);
+
+impl Children {
+    pub fn iter(&self) -> std::slice::Iter<'_, Entity> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, entity_id: Entity) -> bool {
+        self.0.contains(&entity_id)
+    }
+}
+
+fn on_parent_removed(world: &mut World, child: Entity) {
+    let parent = match world.get_component::<Parent>(child) {
+        Some(parent) => parent.get(),
+        None => return,
+    };
+    detach_child(world, parent, child);
+}
+
+fn on_children_removed(world: &mut World, parent: Entity) {
+    let children = match world.get_component::<Children>(parent) {
+        Some(children) => children.0.clone(),
+        None => return,
+    };
+
+    for child in children {
+        let is_child = world
+            .get_component::<Parent>(child)
+            .is_some_and(|child_parent| child_parent.get() == parent);
+
+        if is_child {
+            world.remove_component_from_entity::<Parent>(child);
+        }
+    }
+}
+
+///
+/// ### Description
+///
+/// Removes the child from the [Children] of the parent, removing the
+/// [Children] component once the parent has no children left
+fn detach_child(world: &mut World, parent: Entity, child: Entity) {
+    let is_empty = match world.get_component_mut::<Children>(parent) {
+        Some(children) => {
+            children.0.retain(|entity_id| *entity_id != child);
+            children.is_empty()
+        }
+        None => return,
+    };
+
+    if is_empty {
+        world.remove_component_from_entity::<Children>(parent);
+    }
+}
+
+impl<T: SystemQuery, F: QueryFilter> Query<T, F> {
+    ///
+    /// ### Description
+    ///
+    /// Returns the ancestors of the entity, starting with its parent
+    /// and ending with the root of its hierarchy.
+    ///
+    /// The query must access the [Parent] component, such as a
+    /// `Query<&Parent>` or a `Query<(Entity, &Parent, &Transform)>`.
+    /// Ancestors which are not supplied by the query (due to the filter)
+    /// end the walk.
+    pub fn iter_ancestors(&self, entity_id: Entity) -> Vec<Entity> {
+        verify_query_component::<T, Parent>();
+        collect_ancestors(entity_id, |entity_id| self.get_component::<Parent>(entity_id))
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the descendants of the entity in breadth first order.
+    ///
+    /// The query must access the [Children] component. Descendants whose
+    /// children are not supplied by the query (due to the filter) are
+    /// returned without their children.
+    pub fn iter_descendants(&self, entity_id: Entity) -> Vec<Entity> {
+        verify_query_component::<T, Children>();
+        collect_descendants(entity_id, |entity_id| self.get_component::<Children>(entity_id))
+    }
+}
+
+impl<T: SystemQuery, F: QueryFilter> QueryMut<T, F> {
+    /// See [Query::iter_ancestors]
+    pub fn iter_ancestors(&self, entity_id: Entity) -> Vec<Entity> {
+        verify_query_component::<T, Parent>();
+        collect_ancestors(entity_id, |entity_id| self.get_component::<Parent>(entity_id))
+    }
+
+    /// See [Query::iter_descendants]
+    pub fn iter_descendants(&self, entity_id: Entity) -> Vec<Entity> {
+        verify_query_component::<T, Children>();
+        collect_descendants(entity_id, |entity_id| self.get_component::<Children>(entity_id))
+    }
+}
+
+/// Panics if the query does not access the hierarchy component [`C`]
+fn verify_query_component<T: SystemQuery, C: Component + 'static>() {
+    assert!(
+        T::get_query_component_ids().contains(&TypeId::of::<C>()),
+        "Hierarchy traversal failed: The query does not access the {} component",
+        C::get_name()
+    );
+}
+
+fn collect_ancestors<'q>(entity_id: Entity, get_parent: impl Fn(Entity) -> Option<&'q Parent>) -> Vec<Entity> {
+    let mut ancestors = vec![];
+    let mut current = entity_id;
+
+    while let Some(parent) = get_parent(current) {
+        current = parent.get();
+        ancestors.push(current);
+    }
+    ancestors
+}
+
+fn collect_descendants<'q>(entity_id: Entity, get_children: impl Fn(Entity) -> Option<&'q Children>) -> Vec<Entity> {
+    let mut descendants = vec![];
+    let mut queue = VecDeque::from([entity_id]);
+
+    while let Some(current) = queue.pop_front() {
+        if let Some(children) = get_children(current) {
+            descendants.extend(children.iter());
+            queue.extend(children.iter());
+        }
+    }
+    descendants
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use crate::system::{param::SystemParam, state::SystemState};
+
+    use super::*;
+
+    #[derive(Component)]
+    struct Name;
+
+    /// Creates the hierarchy `root -> [left -> [leaf], right]`
+    fn new_world() -> (World, [Entity; 4]) {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Name>();
+
+        let root = world.spawn(Name);
+        let left = world.spawn(Name);
+        let right = world.spawn(Name);
+        let leaf = world.spawn(Name);
+        world.add_child(root, left);
+        world.add_child(root, right);
+        world.add_child(left, leaf);
+        (world, [root, left, right, leaf])
+    }
+
+    #[test]
+    fn hierarchy_traversal() {
+        let (world, [root, left, right, leaf]) = new_world();
+        let mut state = SystemState::new();
+
+        let (_, parents) = Query::<&Parent>::initialise(&world, &mut state);
+        let parents = parents.expect("The query failed to borrow the components");
+        assert_eq!(parents.iter_ancestors(leaf), [left, root]);
+        assert!(parents.iter_ancestors(root).is_empty());
+        drop(parents);
+
+        let (_, children) = QueryMut::<(Entity, &Children, &mut Name)>::initialise(&world, &mut state);
+        let children = children.expect("The query failed to borrow the components");
+        assert_eq!(children.iter_descendants(root), [left, right, leaf]);
+        assert_eq!(children.iter_descendants(left), [leaf]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The query does not access the Parent component")]
+    fn hierarchy_traversal_requires_component() {
+        let (world, [_, _, _, leaf]) = new_world();
+        let mut state = SystemState::new();
+
+        let (_, names) = Query::<&Name>::initialise(&world, &mut state);
+        names.unwrap().iter_ancestors(leaf);
+    }
+
+    #[test]
+    fn despawn_recursive_removes_descendants() {
+        let (mut world, [root, left, right, leaf]) = new_world();
+        let other = world.spawn(Name);
+        world.add_child(other, right);
+
+        world.despawn_recursive(left);
+        assert!(!world.is_alive(left) && !world.is_alive(leaf));
+        assert!(world.is_alive(root));
+        assert!(!world.has_component::<Children>(root));
+
+        world.despawn_recursive(other);
+        assert!(!world.is_alive(right));
+        assert!(world.is_alive(root));
+    }
+
+    #[test]
+    fn removing_parent_orphans_children() {
+        let (mut world, [root, left, right, leaf]) = new_world();
+
+        world.remove_entity(left);
+        assert!(world.is_alive(leaf));
+        assert!(!world.has_component::<Parent>(leaf));
+        assert!(world.get_component::<Children>(root).is_some_and(|children| !children.contains(left)));
+        assert!(world.get_component::<Children>(root).is_some_and(|children| children.contains(right)));
+    }
+}
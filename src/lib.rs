@@ -1,12 +1,20 @@
+// Allows the derive macros to refer to the crate through
+// the same path inside and outside of the crate
+extern crate self as ecs_rust;
+
 pub mod ecs_base;
 
 pub mod world;
 pub mod app;
+pub mod archetype;
 pub mod component;
 pub mod schedule;
 
 pub mod entity;
 pub mod events;
+pub mod hierarchy;
+pub mod prefab;
+pub mod relation;
 pub mod resource;
 pub mod system;
 
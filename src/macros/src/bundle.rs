@@ -0,0 +1,59 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, Fields, Index};
+
+/// Implementation of the [`Bundle`](crate::Bundle) proc macro
+pub(crate) fn derive_bundle(ast: syn::DeriveInput) -> TokenStream {
+    let type_name = &ast.ident;
+
+    let fields = match &ast.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return syn::Error::new_spanned(type_name, "Bundle can only be derived for structs")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let field_types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let field_accessors: Vec<_> = match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().unwrap();
+                quote! { #ident }
+            })
+            .collect(),
+        Fields::Unnamed(unnamed) => (0..unnamed.unnamed.len())
+            .map(|index| {
+                let index = Index::from(index);
+                quote! { #index }
+            })
+            .collect(),
+        Fields::Unit => vec![],
+    };
+
+    let gen = quote! {
+        impl ecs_rust::component::bundle::Bundle for #type_name {
+            fn collect_component_types(
+                types: &mut ecs_rust::component::bundle::BundleComponentTypes,
+            ) {
+                #(
+                    <#field_types as ecs_rust::component::bundle::Bundle>::collect_component_types(types);
+                )*
+            }
+
+            fn write_components(
+                self,
+                writer: &mut ecs_rust::component::bundle::BundleWriter,
+            ) {
+                #(
+                    ecs_rust::component::bundle::Bundle::write_components(self.#field_accessors, writer);
+                )*
+            }
+        }
+    };
+
+    gen.into()
+}
@@ -1,27 +1,215 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::{parse::ParseStream, Ident, LitStr, Token};
 
 use crate::base::derive_base;
 
 
+/// Arguments supplied to the derive through the
+/// `#[component(...)]` attribute
+#[derive(Default)]
+struct ComponentAttributes {
+    /// `storage = "dense" | "sparse"`
+    storage: Option<proc_macro2::TokenStream>,
+
+    /// `on_add = path`, `on_insert = path`, `on_remove = path`
+    hooks: Vec<(Ident, syn::Path)>,
+
+    /// `requires(Velocity, Mass = default_mass)`
+    requires: Vec<(syn::Type, Option<syn::Expr>)>,
+
+    /// `clone`
+    cloneable: bool,
+}
+
+impl ComponentAttributes {
+    fn from_attributes(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut attributes = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("component")) {
+            attr.parse_args_with(|input: ParseStream| {
+                while !input.is_empty() {
+                    let ident: Ident = input.parse()?;
+
+                    match ident.to_string().as_str() {
+                        "storage" => {
+                            input.parse::<Token![=]>()?;
+                            attributes.storage = Some(Self::parse_storage(input.parse()?)?);
+                        }
+                        "on_add" | "on_insert" | "on_remove" => {
+                            input.parse::<Token![=]>()?;
+                            attributes.hooks.push((ident, input.parse()?));
+                        }
+                        "clone" => attributes.cloneable = true,
+                        "requires" => {
+                            let content;
+                            syn::parenthesized!(content in input);
+                            attributes.requires.extend(Self::parse_requires(&content)?);
+                        }
+                        _ => {
+                            return Err(syn::Error::new(
+                                ident.span(),
+                                format!("Unknown component attribute: {}", ident),
+                            ))
+                        }
+                    }
+
+                    if input.is_empty() {
+                        break;
+                    }
+                    input.parse::<Token![,]>()?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(attributes)
+    }
+
+    /// Parses the comma separated list of required components, each
+    /// optionally followed by `= constructor`
+    fn parse_requires(input: ParseStream) -> syn::Result<Vec<(syn::Type, Option<syn::Expr>)>> {
+        let mut requires = vec![];
+
+        while !input.is_empty() {
+            let component: syn::Type = input.parse()?;
+            let constructor = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            requires.push((component, constructor));
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(requires)
+    }
+
+    fn parse_storage(storage: LitStr) -> syn::Result<proc_macro2::TokenStream> {
+        match storage.value().as_str() {
+            "dense" => Ok(quote! { ecs_rust::component::StorageType::Dense }),
+            "sparse" => Ok(quote! { ecs_rust::component::StorageType::Sparse }),
+            _ => Err(syn::Error::new(
+                storage.span(),
+                "Invalid storage type: Expected \"dense\" or \"sparse\"",
+            )),
+        }
+    }
+}
+
+
+/// Generates the body of `Component::get_name`, which includes
+/// the concrete type (and const) parameters of generic types
+fn derive_name(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let type_name = &ast.ident;
+
+    let params: Vec<_> = ast
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                Some(quote! { ecs_rust::ecs_base::short_type_name::<#ident>() })
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                Some(quote! { #ident.to_string() })
+            }
+            syn::GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+
+    if params.is_empty() {
+        return quote! { String::from(stringify!(#type_name)) };
+    }
+
+    quote! {
+        let params: Vec<String> = vec![#(#params),*];
+        format!("{}<{}>", stringify!(#type_name), params.join(", "))
+    }
+}
 
 
 /// Implementation of the [`Component`](crate::Component) proc macro
 pub(crate) fn derive_component(mut ast: syn::DeriveInput) -> TokenStream {
+    let attributes = match ComponentAttributes::from_attributes(&ast.attrs) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let base_impl = derive_base(&mut ast);
 
-    let type_name = ast.ident;
+    let type_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let name_impl = derive_name(&ast);
 
-    // ast.generics.make_where_clause().predicates.push(parse_quote! {});
+    let storage_impl = attributes.storage.map(|storage| {
+        quote! {
+            fn storage_type() -> ecs_rust::component::StorageType {
+                #storage
+            }
+        }
+    });
+
+    let hooks_impl = (!attributes.hooks.is_empty()).then(|| {
+        let (hook_names, hook_paths): (Vec<_>, Vec<_>) = attributes.hooks.into_iter().unzip();
+        quote! {
+            fn register_hooks(hooks: &mut ecs_rust::component::hooks::ComponentHooks) {
+                #(hooks.#hook_names(#hook_paths);)*
+            }
+        }
+    });
 
-    // let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
+    let requires_impl = (!attributes.requires.is_empty()).then(|| {
+        let requirements = attributes.requires.iter().map(|(component, constructor)| {
+            let constructor = match constructor {
+                Some(constructor) => quote! { #constructor },
+                None => quote! { <#component as ::std::default::Default>::default },
+            };
+            quote! { required.add::<#component>(#constructor); }
+        });
+
+        quote! {
+            fn register_required_components(
+                required: &mut ecs_rust::component::required::RequiredComponents,
+            ) {
+                #(#requirements)*
+            }
+        }
+    });
+
+    let clone_impl = attributes.cloneable.then(|| {
+        quote! {
+            fn clone_function() -> Option<ecs_rust::component::clone::ComponentCloneFunction> {
+                Some(ecs_rust::component::clone::clone_component::<Self>)
+            }
+        }
+    });
 
     let gen = quote! {
         #base_impl
-        impl Component for #type_name {
+        impl #impl_generics Component for #type_name #type_generics #where_clause {
             fn get_name() -> String {
-                String::from(stringify!(#type_name))
+                #name_impl
             }
+
+            #storage_impl
+
+            #hooks_impl
+
+            #requires_impl
+
+            #clone_impl
+        }
+
+        impl #impl_generics ecs_rust::system::param::base_query::QueryComponent for #type_name #type_generics #where_clause {
+            type Access = ecs_rust::system::param::base_query::ParamAccess<Self>;
         }
     };
 
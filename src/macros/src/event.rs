@@ -5,12 +5,13 @@ use crate::base::derive_base;
 
 pub(crate) fn derive_event(mut ast: syn::DeriveInput) -> proc_macro::TokenStream {
     let base_impl = derive_base(&mut ast);
-    let type_name = ast.ident;
+    let type_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
 
     let generate = quote! {
 
         #base_impl
-        impl Event for #type_name {
+        impl #impl_generics Event for #type_name #type_generics #where_clause {
             fn event_type_id(&self) -> std::any::TypeId {
                 std::any::TypeId::of::<Self>()
             }
@@ -6,7 +6,9 @@ use syn::{self, parse_macro_input};
 use utils::AllTuples;
 
 mod base;
+mod bundle;
 mod component;
+mod relation;
 mod resource;
 mod event;
 mod utils;
@@ -23,11 +25,14 @@ mod system_param;
 /// Only after attaching this derive on a Component can we appropriately
 /// add it to a `World` in an app after registering the component in the
 /// app.
+///
+/// The derive also implements `QueryComponent`, letting the type be
+/// named directly in the tuple of a `Query` or a `QueryMut`.
 /// ---
 ///
 /// ### Example:
 ///
-/// ```
+/// ```ignore
 /// #[derive(Component)]
 /// struct Position {
 ///     x: f32,
@@ -37,12 +42,119 @@ mod system_param;
 /// // and be used.
 /// ```
 ///
-#[proc_macro_derive(Component)]
+/// The storage used for the component can be selected with the
+/// `component` attribute. Components use the dense archetype
+/// storage by default.
+///
+/// ```ignore
+/// #[derive(Component)]
+/// #[component(storage = "sparse")]
+/// struct Stunned;
+/// ```
+///
+/// Generic components are supported, and their name includes the
+/// concrete type parameters (`Team<Red>`).
+///
+/// ```ignore
+/// #[derive(Component)]
+/// struct Team<T: 'static> {
+///     _marker: PhantomData<T>
+/// }
+/// ```
+///
+/// Lifecycle hooks of the component can be declared with the same
+/// attribute, each taking the path of a `fn(&mut World, Entity)`.
+///
+/// ```ignore
+/// #[derive(Component)]
+/// #[component(on_add = register_collider, on_remove = unregister_collider)]
+/// struct Collider;
+/// ```
+///
+/// Components which must be present along with the component are
+/// declared with `requires`, and are inserted when missing from the
+/// entity. Required components are constructed with `Default` unless
+/// a constructor is supplied.
+///
+/// ```ignore
+/// #[derive(Component)]
+/// #[component(requires(Velocity, Mass = default_mass))]
+/// struct RigidBody;
+/// ```
+///
+/// Components implementing `Clone` can be marked with `clone`, letting
+/// `World::clone_entity` and prefabs copy them between entities.
+///
+/// ```ignore
+/// #[derive(Component, Clone)]
+/// #[component(clone)]
+/// struct Health(u32);
+/// ```
+///
+#[proc_macro_derive(Component, attributes(component))]
 pub fn component_derive(input: TokenStream) -> TokenStream {
     let ast: syn::DeriveInput = syn::parse(input).unwrap();
     component::derive_component(ast)
 }
 
+/// ### ECS Bundle derive
+///
+/// Implements the `Bundle` trait for a struct whose fields are
+/// components (or other bundles), allowing all of them to be attached
+/// to an entity at once.
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// #[derive(Bundle)]
+/// struct PlayerBundle {
+///     position: Position,
+///     velocity: Velocity,
+/// }
+///
+/// let player = world.spawn(PlayerBundle { ... });
+/// ```
+///
+#[proc_macro_derive(Bundle)]
+pub fn bundle_derive(input: TokenStream) -> TokenStream {
+    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+    bundle::derive_bundle(ast)
+}
+
+/// ### ECS Relation derive
+///
+/// Implements the `Relation` trait for a type, allowing its values to
+/// be used as relations between a source entity and a target entity
+/// through `World::add_relation`.
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// #[derive(Relation)]
+/// struct Likes {
+///     strength: f32
+/// }
+/// ```
+///
+/// The policy applied to the source entity when the target entity is
+/// removed can be selected with the `relation` attribute. Relations are
+/// removed from the source by default.
+///
+/// ```ignore
+/// #[derive(Relation)]
+/// #[relation(cleanup = "despawn_source")]
+/// struct DockedAt;
+/// ```
+///
+#[proc_macro_derive(Relation, attributes(relation))]
+pub fn relation_derive(input: TokenStream) -> TokenStream {
+    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+    relation::derive_relation(ast)
+}
+
 #[proc_macro_derive(Resource)]
 pub fn resource_derive(input: TokenStream) -> TokenStream {
     let ast: syn::DeriveInput = syn::parse(input).unwrap();
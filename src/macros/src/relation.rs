@@ -0,0 +1,90 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse::ParseStream, Ident, LitStr, Token};
+
+
+/// Arguments supplied to the derive through the
+/// `#[relation(...)]` attribute
+#[derive(Default)]
+struct RelationAttributes {
+    /// `cleanup = "remove_relation" | "despawn_source"`
+    cleanup: Option<proc_macro2::TokenStream>,
+}
+
+impl RelationAttributes {
+    fn from_attributes(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut attributes = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("relation")) {
+            attr.parse_args_with(|input: ParseStream| {
+                while !input.is_empty() {
+                    let ident: Ident = input.parse()?;
+
+                    match ident.to_string().as_str() {
+                        "cleanup" => {
+                            input.parse::<Token![=]>()?;
+                            attributes.cleanup = Some(Self::parse_cleanup(input.parse()?)?);
+                        }
+                        _ => {
+                            return Err(syn::Error::new(
+                                ident.span(),
+                                format!("Unknown relation attribute: {}", ident),
+                            ))
+                        }
+                    }
+
+                    if input.is_empty() {
+                        break;
+                    }
+                    input.parse::<Token![,]>()?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(attributes)
+    }
+
+    fn parse_cleanup(cleanup: LitStr) -> syn::Result<proc_macro2::TokenStream> {
+        match cleanup.value().as_str() {
+            "remove_relation" => Ok(quote! { ecs_rust::relation::RelationCleanup::RemoveRelation }),
+            "despawn_source" => Ok(quote! { ecs_rust::relation::RelationCleanup::DespawnSource }),
+            _ => Err(syn::Error::new(
+                cleanup.span(),
+                "Invalid cleanup policy: Expected \"remove_relation\" or \"despawn_source\"",
+            )),
+        }
+    }
+}
+
+
+/// Implementation of the [`Relation`](crate::Relation) proc macro
+pub(crate) fn derive_relation(ast: syn::DeriveInput) -> TokenStream {
+    let attributes = match RelationAttributes::from_attributes(&ast.attrs) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let type_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let cleanup_impl = attributes.cleanup.map(|cleanup| {
+        quote! {
+            fn cleanup_policy() -> ecs_rust::relation::RelationCleanup {
+                #cleanup
+            }
+        }
+    });
+
+    let gen = quote! {
+        impl #impl_generics ecs_rust::relation::Relation for #type_name #type_generics #where_clause {
+            fn get_name() -> String {
+                String::from(stringify!(#type_name))
+            }
+
+            #cleanup_impl
+        }
+    };
+
+    gen.into()
+}
@@ -6,11 +6,12 @@ use crate::base::derive_base;
 
 pub(crate) fn derive_resource(mut ast: DeriveInput) -> proc_macro::TokenStream {
     let base_impl = derive_base(&mut ast);
-    let type_name = ast.ident;
+    let type_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
 
     let generate = quote! {
         #base_impl
-        impl Resource for #type_name {
+        impl #impl_generics Resource for #type_name #type_generics #where_clause {
             // fn get_name(&self) -> String {
             //     String::from(stringify!(#type_name))
             // }
@@ -0,0 +1,184 @@
+use std::any::TypeId;
+
+use crate::{component::Component, entity::Entity, world::World};
+
+/// Type erased insertion of a clone of a prefab component into an entity
+pub type PrefabComponentInsert = Box<dyn Fn(&mut World, Entity) + Send + Sync>;
+
+///
+/// ### Description
+///
+/// A stored template of components, along with a hierarchy of child
+/// templates, which can be instantiated any number of times in a world.
+///
+/// Each instantiation inserts a clone of every component of the template
+/// into a new entity, and instantiates every child template as a
+/// [`child`](crate::hierarchy::Children) of the new entity. The components
+/// of an instance can be overridden while instantiating the prefab through
+/// [World::instantiate_with].
+///
+/// Prefabs are shared across threads (e.g. through an [`Arc`](std::sync::Arc)
+/// moved into the commands of a system), hence the components of a prefab
+/// must be [Send] and [Sync].
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// let goblin = Arc::new(
+///     Prefab::new()
+///         .with(Health(30))
+///         .with(Speed(2.0))
+///         .with_child(Prefab::new().with(Weapon::Dagger)),
+/// );
+///
+/// let goblin_id = world.instantiate(&goblin);
+/// let boss_id = world.instantiate_with(&goblin, |boss| {
+///     boss.insert(Health(300));
+/// });
+///
+/// fn spawner(commands: CommandBufferWriter) {
+///     commands.instantiate(&goblin);
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct Prefab {
+    components: Vec<(TypeId, PrefabComponentInsert)>,
+    children: Vec<Prefab>,
+}
+
+impl Prefab {
+    pub fn new() -> Self {
+        Self {
+            components: vec![],
+            children: vec![],
+        }
+    }
+
+    /// Adds the component to the template, replacing the
+    /// component of the same type if one was already added
+    pub fn with<C: Component + Clone + Send + Sync + 'static>(mut self, component: C) -> Self {
+        self.components.retain(|(component_id, _)| *component_id != TypeId::of::<C>());
+        self.components.push((
+            TypeId::of::<C>(),
+            Box::new(move |world, entity_id| world.insert_component(entity_id, component.clone())),
+        ));
+        self
+    }
+
+    /// Adds a child template, instantiated as a child of every instance of the template
+    pub fn with_child(mut self, child: Prefab) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn contains<C: Component + 'static>(&self) -> bool {
+        self.components
+            .iter()
+            .any(|(component_id, _)| *component_id == TypeId::of::<C>())
+    }
+
+    pub fn children(&self) -> &[Prefab] {
+        &self.children
+    }
+
+    /// Returns the number of components of the template, excluding the children
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Inserts the components of the template into the entity, and
+    /// instantiates the child templates as children of the entity
+    pub(crate) fn write(&self, world: &mut World, entity_id: Entity) {
+        for (_, insert) in &self.components {
+            insert(world, entity_id);
+        }
+
+        for child in &self.children {
+            let child_id = world.create_entity();
+            child.write(world, child_id);
+            world.set_parent(child_id, entity_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::{
+        ecs_base::ECSBase,
+        hierarchy::{Children, Parent},
+    };
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    #[component(clone)]
+    struct Health(u32);
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Speed(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Target(Entity);
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Health>();
+        world.register_component::<Speed>();
+        world.register_component::<Target>();
+        world
+    }
+
+    #[test]
+    fn clones_copy_the_cloneable_components() {
+        let mut world = new_world();
+        world.register_cloneable::<Speed>();
+        let parent = world.create_entity();
+        let source = world.spawn((Health(10), Speed(2), Target(parent)));
+        world.set_parent(source, parent);
+
+        let clone = world.clone_entity(source);
+        let entity = world.entity(clone);
+        assert_eq!(entity.get::<Health>(), Some(&Health(10)));
+        assert_eq!(entity.get::<Speed>(), Some(&Speed(2)));
+        assert!(!entity.contains::<Target>());
+        assert!(!entity.contains::<Parent>());
+
+        world.remove_entity(source);
+        assert_eq!(world.clone_entity(source), Entity::PLACEHOLDER);
+    }
+
+    #[test]
+    fn prefabs_instantiate_their_children() {
+        let mut world = new_world();
+        let prefab = Prefab::new()
+            .with(Health(30))
+            .with(Health(40))
+            .with_child(Prefab::new().with(Speed(1)));
+        assert_eq!(prefab.len(), 1);
+
+        let instance = world.instantiate(&prefab);
+        let boss = world.instantiate_with(&prefab, |boss| {
+            boss.insert(Health(300));
+        });
+
+        assert_eq!(world.entity(instance).get::<Health>(), Some(&Health(40)));
+        assert_eq!(world.entity(boss).get::<Health>(), Some(&Health(300)));
+
+        let children = world.entity(instance).get::<Children>().unwrap();
+        assert_eq!(children.len(), 1);
+        let child = *children.iter().next().unwrap();
+        assert_eq!(world.entity(child).get::<Parent>().map(Parent::get), Some(instance));
+        assert_eq!(world.entity(child).get::<Speed>(), Some(&Speed(1)));
+    }
+}
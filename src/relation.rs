@@ -0,0 +1,426 @@
+use std::{
+    any::TypeId,
+    marker::PhantomData,
+    sync::{Arc, RwLock},
+};
+
+use hashbrown::HashMap;
+
+use crate::{
+    component::{hooks::ComponentHooks, Component, StorageType},
+    ecs_base::ECSBase,
+    entity::Entity,
+    system::{
+        param::{
+            base_query::{ParamAccess, QueryComponent, SystemQuery},
+            InitError, Query, QueryFilter, QueryMut, SystemParam,
+        },
+        state::SystemState,
+    },
+    world::World,
+};
+use ecs_macros::{ECSBase, SystemParam};
+
+///
+/// ### Description
+///
+/// A kind of relationship between a source entity and a target entity,
+/// such as `Likes` or `DockedAt`. The relation value may hold data about
+/// the relationship.
+///
+/// An entity can hold several relations of the same kind, each with a
+/// different target. The relations of an entity are stored in its
+/// [Relations] component, and are managed through [World::add_relation]
+/// and [World::remove_relation].
+///
+/// This trait must be implemented using the [`Relation`](ecs_macros::Relation)
+/// derive macro. The [RelationCleanup] policy applied when the target is
+/// removed can be selected with the `#[relation(cleanup = "despawn_source")]`
+/// attribute.
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// #[derive(Relation)]
+/// #[relation(cleanup = "despawn_source")]
+/// struct DockedAt;
+///
+/// world.add_relation(ship, station, DockedAt);
+///
+/// // Removing the station also removes every ship docked at it
+/// world.remove_entity(station);
+/// ```
+///
+pub trait Relation: 'static {
+    fn get_name() -> String
+    where
+        Self: Sized;
+
+    /// Returns the policy applied to the source entity of the
+    /// relation when its target entity is removed from the world
+    fn cleanup_policy() -> RelationCleanup
+    where
+        Self: Sized,
+    {
+        RelationCleanup::RemoveRelation
+    }
+}
+
+///
+/// ### Description
+///
+/// The policy applied to the source entity of a [Relation]
+/// when the target entity of the relation is removed
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationCleanup {
+    /// The relation is removed from the source entity
+    RemoveRelation,
+
+    /// The source entity is removed from the world
+    DespawnSource,
+}
+
+///
+/// ### Description
+///
+/// Component holding all the relations of kind [`R`] of an entity,
+/// along with their targets.
+///
+/// Queries match entities on the kind of relation through this
+/// component, and match on the targets through its methods:
+///
+/// ```ignore
+/// fn system(query: Query<(Entity, Relations<Likes>)>, likes: RelationSources<Likes>) {
+///     // Any target (wildcard)
+///     for (entity, likes) in query.iter() {
+///         for (target, like) in likes.iter() { ... }
+///     }
+///
+///     // A specific target, see RelationSources
+///     for (entity, like) in query.iter_targeting(&likes, player) { ... }
+/// }
+/// ```
+///
+/// The component uses the [`sparse`](StorageType::Sparse) storage, since
+/// relations are expected to be added and removed often.
+///
+#[derive(ECSBase)]
+pub struct Relations<R: Relation> {
+    pairs: Vec<(Entity, R)>,
+}
+
+impl<R: Relation> Relations<R> {
+    pub(crate) fn new(target: Entity, relation: R) -> Self {
+        Self {
+            pairs: vec![(target, relation)],
+        }
+    }
+
+    /// Returns the relation targeting the given entity
+    pub fn get(&self, target: Entity) -> Option<&R> {
+        self.pairs
+            .iter()
+            .find(|(entity_id, _)| *entity_id == target)
+            .map(|(_, relation)| relation)
+    }
+
+    pub fn get_mut(&mut self, target: Entity) -> Option<&mut R> {
+        self.pairs
+            .iter_mut()
+            .find(|(entity_id, _)| *entity_id == target)
+            .map(|(_, relation)| relation)
+    }
+
+    pub fn contains(&self, target: Entity) -> bool {
+        self.get(target).is_some()
+    }
+
+    /// Returns the targets of the relations
+    pub fn targets(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.pairs.iter().map(|(entity_id, _)| *entity_id)
+    }
+
+    /// Returns the relations along with their targets
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &R)> {
+        self.pairs.iter().map(|(entity_id, relation)| (*entity_id, relation))
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Inserts the relation, returning the previous relation
+    /// with the same target if one existed
+    pub(crate) fn insert(&mut self, target: Entity, relation: R) -> Option<R> {
+        match self.get_mut(target) {
+            Some(existing) => Some(std::mem::replace(existing, relation)),
+            None => {
+                self.pairs.push((target, relation));
+                None
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, target: Entity) -> Option<R> {
+        let index = self.pairs.iter().position(|(entity_id, _)| *entity_id == target)?;
+        Some(self.pairs.swap_remove(index).1)
+    }
+}
+
+impl<R: Relation> Component for Relations<R> {
+    fn get_name() -> String {
+        format!("Relations<{}>", R::get_name())
+    }
+
+    fn storage_type() -> StorageType {
+        StorageType::Sparse
+    }
+
+    fn register_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(on_relations_removed::<R>);
+    }
+}
+
+impl<R: Relation> QueryComponent for Relations<R> {
+    type Access = ParamAccess<Self>;
+}
+
+/// Drops the relations of the source entity from the relation index
+/// of the world, since the relations no longer need cleaning up
+fn on_relations_removed<R: Relation>(world: &mut World, source: Entity) {
+    world.unindex_relations::<R>(source);
+}
+
+/// Applies the [RelationCleanup] policy of the relation kind [`R`]
+/// to the source entity, once the target entity has been removed
+pub(crate) fn on_target_removed<R: Relation>(world: &mut World, source: Entity, target: Entity) {
+    match R::cleanup_policy() {
+        RelationCleanup::RemoveRelation => world.remove_relation::<R>(source, target),
+        RelationCleanup::DespawnSource => world.remove_entity(source),
+    }
+}
+
+/// A relation pointing to a target entity, recorded in the [RelationIndex]
+pub(crate) struct RelationSource {
+    pub(crate) source: Entity,
+    pub(crate) kind: TypeId,
+
+    /// Cleanup of the relation, called with the source and the target
+    pub(crate) on_target_removed: fn(&mut World, Entity, Entity),
+}
+
+///
+/// ### Description
+///
+/// Reverse index of the relations of a [`world`](World), mapping
+/// every target entity to the relations pointing to it.
+///
+/// The index allows the world to apply the [RelationCleanup] policies
+/// when a target entity is removed, without visiting every entity.
+///
+pub(crate) struct RelationIndex {
+    sources: HashMap<Entity, Vec<RelationSource>>,
+}
+
+impl RelationIndex {
+    pub fn new() -> Self {
+        Self {
+            sources: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, target: Entity, relation_source: RelationSource) {
+        self.sources.entry(target).or_default().push(relation_source);
+    }
+
+    pub fn remove(&mut self, target: Entity, source: Entity, kind: TypeId) {
+        if let Some(sources) = self.sources.get_mut(&target) {
+            sources.retain(|relation_source| relation_source.source != source || relation_source.kind != kind);
+            if sources.is_empty() {
+                self.sources.remove(&target);
+            }
+        }
+    }
+
+    /// Removes and returns all the relations pointing to the target
+    pub fn take(&mut self, target: Entity) -> Vec<RelationSource> {
+        self.sources.remove(&target).unwrap_or_default()
+    }
+
+    /// Returns the sources of the relations of the given kind pointing to the target
+    pub fn get_sources(&self, target: Entity, kind: TypeId) -> Vec<Entity> {
+        self.sources.get(&target).map_or(vec![], |sources| {
+            sources
+                .iter()
+                .filter(|relation_source| relation_source.kind == kind)
+                .map(|relation_source| relation_source.source)
+                .collect()
+        })
+    }
+}
+
+///
+/// ### Description
+///
+/// A [`system param`](crate::system::param::SystemParam) answering which
+/// entities hold a relation of kind [`R`] targeting a given entity, through
+/// the [RelationIndex] of the world rather than by visiting every entity.
+///
+/// The relations themselves are read through a query accessing the
+/// [Relations] component, see [Query::iter_targeting].
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// fn system(query: Query<(&Relations<Likes>, &Name)>, likes: RelationSources<Likes>) {
+///     for (source, like) in query.iter_targeting(&likes, player) { ... }
+/// }
+/// ```
+///
+#[derive(SystemParam)]
+pub struct RelationSources<R: Relation> {
+    index: Arc<RwLock<RelationIndex>>,
+    _relation: PhantomData<R>,
+}
+
+impl<R: Relation> RelationSources<R> {
+    /// Returns the entities holding a relation of kind [`R`]
+    /// which targets the given entity
+    pub fn get(&self, target: Entity) -> Vec<Entity> {
+        self.index.read().unwrap().get_sources(target, TypeId::of::<R>())
+    }
+}
+
+impl<R: Relation> SystemParam for RelationSources<R> {
+    fn initialise(world: &World, _state: &mut SystemState) -> (Option<InitError>, Option<Self>)
+    where
+        Self: Sized,
+    {
+        (
+            None,
+            Some(Self {
+                index: world.get_relation_index(),
+                _relation: PhantomData,
+            }),
+        )
+    }
+
+    // The index is only modified through a mutable reference to
+    // the world, hence it never conflicts with another system
+    fn get_resource_access_type() -> hashbrown::HashSet<TypeId> {
+        hashbrown::HashSet::new()
+    }
+
+    fn is_resource_access_mut(_resource_id: TypeId) -> bool {
+        false
+    }
+}
+
+impl<T: SystemQuery, F: QueryFilter> Query<T, F> {
+    ///
+    /// ### Description
+    ///
+    /// Returns the entities of the query holding a relation of kind [`R`]
+    /// which targets the given entity, along with the relation.
+    ///
+    /// The sources are looked up in the relation index, then the relations
+    /// of every source are fetched through the entity index of the query,
+    /// which must access the [Relations] component of kind [`R`].
+    pub fn iter_targeting<'q, R: Relation>(
+        &'q self,
+        sources: &RelationSources<R>,
+        target: Entity,
+    ) -> impl Iterator<Item = (Entity, &'q R)> + 'q {
+        verify_relation_query::<T, R>();
+        sources.get(target).into_iter().filter_map(move |source| {
+            let relation = self.get_component::<Relations<R>>(source)?.get(target)?;
+            Some((source, relation))
+        })
+    }
+}
+
+impl<T: SystemQuery, F: QueryFilter> QueryMut<T, F> {
+    /// See [Query::iter_targeting]
+    pub fn iter_targeting<'q, R: Relation>(
+        &'q self,
+        sources: &RelationSources<R>,
+        target: Entity,
+    ) -> impl Iterator<Item = (Entity, &'q R)> + 'q {
+        verify_relation_query::<T, R>();
+        sources.get(target).into_iter().filter_map(move |source| {
+            let relation = self.get_component::<Relations<R>>(source)?.get(target)?;
+            Some((source, relation))
+        })
+    }
+}
+
+/// Panics if the query does not access the [Relations] of kind [`R`]
+fn verify_relation_query<T: SystemQuery, R: Relation>() {
+    assert!(
+        T::get_query_component_ids().contains(&TypeId::of::<Relations<R>>()),
+        "Relation lookup failed: The query does not access the {} component",
+        Relations::<R>::get_name()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::{Component, Relation};
+
+    use super::*;
+
+    #[derive(Relation)]
+    struct Likes(u32);
+
+    #[derive(Relation)]
+    struct Fears;
+
+    #[derive(Component)]
+    struct Name;
+
+    #[test]
+    fn relation_sources_are_indexed_by_target() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Name>();
+
+        let target = world.spawn(Name);
+        let other_target = world.spawn(Name);
+        let first = world.spawn(Name);
+        let second = world.spawn(Name);
+        world.add_relation(first, target, Likes(1));
+        world.add_relation(first, other_target, Likes(2));
+        world.add_relation(second, target, Fears);
+        assert_eq!(world.get_relation_sources::<Likes>(target), [first]);
+
+        let mut state = SystemState::new();
+        let (_, sources) = RelationSources::<Likes>::initialise(&world, &mut state);
+        let sources = sources.unwrap();
+        let (_, query) = Query::<(&Name, &Relations<Likes>)>::initialise(&world, &mut state);
+        let query = query.expect("The query failed to borrow the components");
+
+        let targeting: Vec<(Entity, u32)> = query
+            .iter_targeting(&sources, target)
+            .map(|(source, like)| (source, like.0))
+            .collect();
+        assert_eq!(targeting, [(first, 1)]);
+        assert_eq!(query.iter_targeting(&sources, other_target).count(), 1);
+        assert_eq!(query.iter_targeting(&sources, first).count(), 0);
+        drop(query);
+
+        world.remove_relation::<Likes>(first, target);
+        assert!(sources.get(target).is_empty());
+        assert_eq!(sources.get(other_target), [first]);
+    }
+}
@@ -1,6 +1,7 @@
 use super::{
     dependency::{SystemDependencies, SystemMetadata},
     param::{InitError, SystemParam},
+    state::SystemState,
 };
 use crate::world::World;
 use ecs_macros::implement_tuples;
@@ -26,6 +27,7 @@ pub trait SystemExtractor<MarkerFunc> {
     fn extract_dependencies(
         &mut self,
         world: &World,
+        state: &mut SystemState,
         deps: &mut SystemDependencies,
     ) -> Option<InitError>;
 }
@@ -105,7 +107,13 @@ macro_rules! impl_system_function {
             }
 
             // See description in [SystemExtractor]
-            fn extract_dependencies(&mut self, world: &World, dependencies: &mut SystemDependencies) -> Option<InitError> {
+            #[allow(unused_variables)]
+            fn extract_dependencies(
+                &mut self,
+                world: &World,
+                state: &mut SystemState,
+                dependencies: &mut SystemDependencies,
+            ) -> Option<InitError> {
                 // Create extractor instances for supplied extractor types.
                 $(
 
@@ -117,7 +125,7 @@ macro_rules! impl_system_function {
                     // InitError, means that the requested resoruce does not 
                     // exist in the world, in which case we want to stop the
                     // system from executing
-                    let $param = match $param::initialise(world) {
+                    let $param = match $param::initialise(world, state) {
                         (None, Some(x)) => x,
 
                         
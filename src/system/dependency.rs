@@ -75,9 +75,9 @@ impl SystemMetadata {
     /// 
     /// This function is responsible for 2 main things
     /// 1. Keeping a track record of dependency for scheduler to check and
-    /// schedule the system accordingly
+    ///    schedule the system accordingly
     /// 2. Checking and reporting any possible internal dependency conflicts
-    /// See notes.md for more on Internal Dependency Error
+    ///    See notes.md for more on Internal Dependency Error
     pub fn push_dependency_metadata<S: SystemParam + 'static>(&mut self) {
 
         // Internal dependency check
@@ -88,12 +88,12 @@ impl SystemMetadata {
                 // mut-mut collision
                 // mut-read collision
 
-                if *self.dependency_metadata.get(&rid).unwrap() || S::is_resource_access_mut() {
+                if *self.dependency_metadata.get(&rid).unwrap() || S::is_resource_access_mut(rid) {
                     panic!("Internal dependency error found")
                 }
             } else {
                 self.dependency_metadata
-                    .insert(rid, S::is_resource_access_mut());
+                    .insert(rid, S::is_resource_access_mut(rid));
             }
         }
 
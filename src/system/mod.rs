@@ -1,6 +1,7 @@
 pub mod base;
 pub mod dependency;
 pub mod param;
+pub mod state;
 
 use std::marker::PhantomData;
 
@@ -11,6 +12,7 @@ use self::{
     base::{SystemExecutor, SystemExtractor, SystemMarker},
     dependency::{SystemDependencies, SystemMetadata},
     param::InitError,
+    state::SystemState,
 };
 use crate::schedule::schedulable::Schedulable;
 
@@ -26,6 +28,7 @@ where
 {
     pub(crate) func: Func,
     pub(crate) dependencies: SystemDependencies,
+    pub(crate) state: SystemState,
     pub(crate) _marker: PhantomData<Marker>,
 }
 
@@ -65,8 +68,9 @@ where
 
     /// For description, see [Schedulable::initialise_dependencies]
     fn initialise_dependencies(&mut self, world: &World) -> Option<InitError> {
+        self.state.begin_run(world.increment_change_tick());
         self.func
-            .extract_dependencies(world, &mut self.dependencies)
+            .extract_dependencies(world, &mut self.state, &mut self.dependencies)
     }
 
 
@@ -78,6 +82,9 @@ where
         // This clears out the acquired locks stored in the
         // [`dependencies`](crate::system::System::dependencies)
         self.func.run(&mut self.dependencies);
+
+        // Changes made from here on are detected in the next run
+        self.state.end_run();
     }
 }
 
@@ -99,6 +106,7 @@ where
         Self {
             func: system,
             dependencies: SystemDependencies::new(),
+            state: SystemState::new(),
             _marker: std::marker::PhantomData,
         }
     }
@@ -1,7 +1,10 @@
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc};
 
+use crate::component::bundle::Bundle;
 use crate::ecs_base::ECSBase;
-use crate::system::SystemParam;
+use crate::entity::{entity_manager::EntityReserver, Entity};
+use crate::prefab::Prefab;
+use crate::system::{state::SystemState, SystemParam};
 use crate::world::World;
 use ecs_macros::SystemParam;
 
@@ -10,11 +13,14 @@ use super::InitError;
 #[derive(SystemParam)]
 pub struct CommandBufferWriter {
     // pub(crate) writer_channel: Sender<CommandFunction>,
-    pub(crate) writer_channel: Sender<Box<dyn FnMut(&mut World) -> ()>>,
+    pub(crate) writer_channel: Sender<Box<dyn FnMut(&mut World) + Send>>,
+
+    /// Reserver of the entity ids of the world
+    pub(crate) entity_reserver: Arc<EntityReserver>,
 }
 
 impl SystemParam for CommandBufferWriter {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>)
+    fn initialise(world: &World, _state: &mut SystemState) -> (Option<InitError>, Option<Self>)
     where
         Self: Sized,
     {
@@ -22,6 +28,7 @@ impl SystemParam for CommandBufferWriter {
             None,
             Some(Self {
                 writer_channel: (*world).get_command_writer(),
+                entity_reserver: world.get_entity_reserver(),
             }),
         )
     }
@@ -30,7 +37,7 @@ impl SystemParam for CommandBufferWriter {
         hashbrown::HashSet::new()
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: std::any::TypeId) -> bool {
         false
     }
 }
@@ -39,7 +46,56 @@ impl CommandBufferWriter {
     // pub fn add_command(&self, func: fn(&mut  World) -> ()) {
     //     self.writer_channel.send(func);
     // }
-    pub fn add_command<Func: FnMut(&mut World) + 'static>(&self, func: Func) {
+    ///
+    /// ### Description
+    ///
+    /// Adds a command, run on the [World] once every system of the
+    /// schedule has run.
+    ///
+    /// The command is sent from the thread executing the system, hence it
+    /// must be [Send]. The handles supplied by the other parameters of the
+    /// system can not be moved into the command.
+    pub fn add_command<Func: FnMut(&mut World) + Send + 'static>(&self, func: Func) {
         let _ = self.writer_channel.send(Box::new(func));
     }
+
+    ///
+    /// ### Description
+    ///
+    /// Reserves an [`entity id`](Entity) which the commands of the system
+    /// can operate on. See [World::reserve_entity]
+    pub fn reserve_entity(&self) -> Entity {
+        self.entity_reserver.reserve_entity()
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Reserves an entity and adds a command inserting the bundle into it,
+    /// returning the reserved [`entity id`](Entity)
+    pub fn spawn<B: Bundle + Send>(&self, bundle: B) -> Entity {
+        let entity_id = self.reserve_entity();
+
+        let mut bundle = Some(bundle);
+        self.add_command(move |world| {
+            if let Some(bundle) = bundle.take() {
+                world.insert_bundle(entity_id, bundle);
+            }
+        });
+        entity_id
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Reserves an entity and adds a command instantiating the [Prefab]
+    /// into it, returning the reserved [`entity id`](Entity).
+    /// See [World::instantiate]
+    pub fn instantiate(&self, prefab: &Arc<Prefab>) -> Entity {
+        let entity_id = self.reserve_entity();
+
+        let prefab = prefab.clone();
+        self.add_command(move |world| world.instantiate_into(&prefab, entity_id));
+        entity_id
+    }
 }
@@ -1,8 +1,12 @@
 use ecs_macros::SystemParam;
 
-use crate::component::handles::{ComponentHandle, MutComponentHandle};
+use crate::component::borrow::ComponentBorrows;
+use crate::component::handles::{
+    ComponentHandle, ComponentPtr, HandleIter, HandleIterMut, MutComponentHandle, MutComponentPtr,
+};
 use crate::component::Component;
 use crate::system::param::{InitError, SystemParam};
+use crate::system::state::SystemState;
 use crate::world::World;
 use crate::ecs_base::ECSBase;
 
@@ -16,59 +20,56 @@ use crate::ecs_base::ECSBase;
 ///
 #[derive(SystemParam)]
 pub struct ComponentCollection<C: Component + 'static> {
-    locks: Vec<ComponentHandle<C>>,
+    handles: Vec<ComponentPtr<C>>,
+
+    /// Borrows on the component storages, released when the
+    /// collection is dropped
+    _borrows: ComponentBorrows,
 }
 
 impl<C: Component + 'static> ComponentCollection<C> {
-    pub fn get_conditional_collection<Func: FnMut(&mut ComponentHandle<C>) -> bool>(
-        &mut self,
+    pub fn iter(&self) -> HandleIter<'_, ComponentPtr<C>> {
+        HandleIter::new(&self.handles)
+    }
+
+    pub fn get_conditional_collection<Func: FnMut(&ComponentHandle<'_, C>) -> bool>(
+        &self,
         mut filter_func: Func
-    ) -> Vec<&mut ComponentHandle<C>> {
-        self.locks.iter_mut().filter_map(|handle| {
-            if !(filter_func)(handle) {
-                return None
-            }
-            Some(handle)
-        }).collect()
+    ) -> Vec<ComponentHandle<'_, C>> {
+        self.iter().filter(|handle| (filter_func)(handle)).collect()
     }
 }
 
 
 
-impl<C: Component + 'static> IntoIterator for ComponentCollection<C> {
-    type Item = ComponentHandle<C>;
+impl<'c, C: Component + 'static> IntoIterator for &'c ComponentCollection<C> {
+    type Item = ComponentHandle<'c, C>;
 
-    type IntoIter = std::vec::IntoIter<ComponentHandle<C>>;
+    type IntoIter = HandleIter<'c, ComponentPtr<C>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.locks.into_iter()
+        self.iter()
     }
 }
 
 impl<C: Component + 'static> SystemParam for ComponentCollection<C> {
-    fn initialise(world: &World) -> (Option<super::InitError>, Option<Self>)
+    fn initialise(world: &World, state: &mut SystemState) -> (Option<super::InitError>, Option<Self>)
     where
         Self: Sized,
     {
-        let self_obj = Self {
-            locks: match world.get_all_component_locks::<C>() {
-                Some(x) => {
-                    let lock_vec: Vec<ComponentHandle<C>> = x
-                        .into_iter()
-                        .map(|(entity, lock)| ComponentHandle::new(lock, entity))
-                        .collect();
-
-                    if lock_vec.len() == 0 {
-                        return (Some(InitError {}), None);
-                    }
-
-                    lock_vec
+        let mut borrows = ComponentBorrows::new();
+        let handles = match world.get_all_components::<C>(state, &mut borrows) {
+            Some(x) => {
+                if x.is_empty() {
+                    return (Some(InitError {}), None);
                 }
-                None => return (Some(InitError {}), None),
-            },
+
+                x
+            }
+            None => return (Some(InitError {}), None),
         };
 
-        return (None, Some(self_obj));
+        (None, Some(Self { handles, _borrows: borrows }))
     }
 
     fn get_resource_access_type() -> hashbrown::HashSet<std::any::TypeId> {
@@ -77,7 +78,7 @@ impl<C: Component + 'static> SystemParam for ComponentCollection<C> {
         hash_set
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: std::any::TypeId) -> bool {
         false
     }
 }
@@ -92,16 +93,28 @@ impl<C: Component + 'static> SystemParam for ComponentCollection<C> {
 ///
 #[derive(SystemParam)]
 pub struct ComponentCollectionMut<C: Component + 'static> {
-    locks: Vec<MutComponentHandle<C>>,
+    handles: Vec<MutComponentPtr<C>>,
+
+    /// Borrows on the component storages, released when the
+    /// collection is dropped
+    _borrows: ComponentBorrows,
 }
 
 impl<C: Component + 'static> ComponentCollectionMut<C> {
-    pub fn get_conditional_collection<Func: FnMut(&mut MutComponentHandle<C>) -> bool>(
+    pub fn iter(&self) -> HandleIter<'_, MutComponentPtr<C>> {
+        HandleIter::new(&self.handles)
+    }
+
+    pub fn iter_mut(&mut self) -> HandleIterMut<'_, MutComponentPtr<C>> {
+        HandleIterMut::new(&mut self.handles)
+    }
+
+    pub fn get_conditional_collection<Func: FnMut(&mut MutComponentHandle<'_, C>) -> bool>(
         &mut self,
         mut filter_func: Func
-    ) -> Vec<&mut MutComponentHandle<C>> {
-        self.locks.iter_mut().filter_map(|handle| {
-            if !(filter_func)(handle) {
+    ) -> Vec<MutComponentHandle<'_, C>> {
+        self.iter_mut().filter_map(|mut handle| {
+            if !(filter_func)(&mut handle) {
                 return None
             }
             Some(handle)
@@ -109,40 +122,44 @@ impl<C: Component + 'static> ComponentCollectionMut<C> {
     }
 }
 
-impl<C: Component + 'static> IntoIterator for ComponentCollectionMut<C> {
-    type Item = MutComponentHandle<C>;
+impl<'c, C: Component + 'static> IntoIterator for &'c ComponentCollectionMut<C> {
+    type Item = ComponentHandle<'c, C>;
 
-    type IntoIter = std::vec::IntoIter<MutComponentHandle<C>>;
+    type IntoIter = HandleIter<'c, MutComponentPtr<C>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.locks.into_iter()
+        self.iter()
+    }
+}
+
+impl<'c, C: Component + 'static> IntoIterator for &'c mut ComponentCollectionMut<C> {
+    type Item = MutComponentHandle<'c, C>;
+
+    type IntoIter = HandleIterMut<'c, MutComponentPtr<C>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
 impl<C: Component + 'static> SystemParam for ComponentCollectionMut<C> {
-    fn initialise(world: &World) -> (Option<super::InitError>, Option<Self>)
+    fn initialise(world: &World, state: &mut SystemState) -> (Option<super::InitError>, Option<Self>)
     where
         Self: Sized,
     {
-        let self_obj = Self {
-            locks: match world.get_all_component_locks_mut::<C>() {
-                Some(x) => {
-                    let lock_vec: Vec<MutComponentHandle<C>> = x
-                        .into_iter()
-                        .map(|(entity, lock)| MutComponentHandle::new(lock, entity))
-                        .collect();
-
-                    if lock_vec.len() == 0 {
-                        return (Some(InitError {}), None);
-                    }
-
-                    lock_vec
+        let mut borrows = ComponentBorrows::new();
+        let handles = match world.get_all_components_mut::<C>(state, &mut borrows) {
+            Some(x) => {
+                if x.is_empty() {
+                    return (Some(InitError {}), None);
                 }
-                None => return (Some(InitError {}), None),
-            },
+
+                x
+            }
+            None => return (Some(InitError {}), None),
         };
 
-        return (None, Some(self_obj));
+        (None, Some(Self { handles, _borrows: borrows }))
     }
 
     fn get_resource_access_type() -> hashbrown::HashSet<std::any::TypeId> {
@@ -151,7 +168,7 @@ impl<C: Component + 'static> SystemParam for ComponentCollectionMut<C> {
         hash_set
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: std::any::TypeId) -> bool {
         true
     }
 }
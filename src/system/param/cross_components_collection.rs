@@ -1,13 +1,12 @@
 use ecs_macros::SystemParam;
-use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard};
 
 use crate::{
     component::{
-        handles::{ComponentRefHandle, MutComponentRefHandle},
+        borrow::ComponentBorrows,
+        handles::{ComponentPtr, ComponentRefHandle, HandleIter, MutComponentPtr, MutComponentRefHandle, RawHandle},
         Component,
     },
-    entity::Entity,
-    system::param::SystemParam,
+    system::{param::SystemParam, state::SystemState},
     world::World,
     ecs_base::ECSBase
 };
@@ -30,18 +29,23 @@ use super::InitError;
 ///
 #[derive(SystemParam)]
 pub struct CrossComponentCollection<C: Component + 'static> {
-    /// Storing the acquired locks in the vector to later
+    /// Storing the acquired handles in the vector to later
     /// create appropriate handles by using reference to
-    /// the read guards stored here
-    component_vec: Vec<(Entity, OwnedRwLockReadGuard<C>)>,
+    /// the components stored here
+    component_vec: Vec<ComponentPtr<C>>,
+
+    /// Borrows on the component storages, released when the
+    /// collection is dropped
+    _borrows: ComponentBorrows,
 }
 
 impl<C: Component + 'static> SystemParam for CrossComponentCollection<C> {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>)
+    fn initialise(world: &World, state: &mut SystemState) -> (Option<InitError>, Option<Self>)
     where
         Self: Sized,
     {
-        let component_vec = match world.get_all_component_locks::<C>() {
+        let mut borrows = ComponentBorrows::new();
+        let component_vec = match world.get_all_components::<C>(state, &mut borrows) {
             Some(x) => {
                 if x.len() < 2 {
                     // This type of system should not execute
@@ -53,7 +57,13 @@ impl<C: Component + 'static> SystemParam for CrossComponentCollection<C> {
             None => return (Some(InitError {}), None),
         };
 
-        (None, Some(Self { component_vec }))
+        (
+            None,
+            Some(Self {
+                component_vec,
+                _borrows: borrows,
+            }),
+        )
     }
 
     fn get_resource_access_type() -> hashbrown::HashSet<std::any::TypeId> {
@@ -62,7 +72,7 @@ impl<C: Component + 'static> SystemParam for CrossComponentCollection<C> {
         hash_set
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: std::any::TypeId) -> bool {
         false
     }
 }
@@ -103,42 +113,32 @@ impl<C: Component + 'static> CrossComponentCollection<C> {
 /// Private methods for [CrossComponentCollection]
 impl<C: Component + 'static> CrossComponentCollection<C> {
     fn execute_handler_on_component_arr<Func, FilterFunc>(
-        components: &Vec<(Entity, OwnedRwLockReadGuard<C>)>,
+        components: &[ComponentPtr<C>],
         mut execution_func: Func,
         mut filter_func: FilterFunc,
     ) where
         Func: FnMut(ComponentRefHandle<C>, ComponentRefHandle<C>),
         FilterFunc: FnMut(ComponentRefHandle<C>) -> bool,
     {
-        let mut component_vec: Vec<&(Entity, OwnedRwLockReadGuard<C>)> = components
-            .iter()
-            .filter_map(|tuple| {
-                let handle = ComponentRefHandle {
-                    entity_id: tuple.0,
-                    inner: &tuple.1,
-                };
-
-                if !filter_func(handle) {
-                    return None;
-                }
-                Some(tuple)
+        let component_vec: Vec<_> = HandleIter::new(components)
+            .filter(|handle| {
+                filter_func(ComponentRefHandle {
+                    entity_id: handle.entity_id,
+                    inner: *handle,
+                })
             })
             .collect();
 
         for i in 0..component_vec.len() {
             for j in (i + 1)..component_vec.len() {
-                let mut_slice = component_vec.as_mut_slice();
-                let (first, last) = mut_slice.split_at_mut(i + 1);
-                let first_len = first.len();
-
                 let a = ComponentRefHandle {
-                    entity_id: first[first.len() - 1].0,
-                    inner: &first[first.len() - 1].1,
+                    entity_id: component_vec[i].entity_id,
+                    inner: component_vec[i],
                 };
 
                 let b = ComponentRefHandle {
-                    entity_id: last[j - first_len].0,
-                    inner: &last[j - first_len].1,
+                    entity_id: component_vec[j].entity_id,
+                    inner: component_vec[j],
                 };
 
                 (execution_func)(a, b);
@@ -163,15 +163,20 @@ impl<C: Component + 'static> CrossComponentCollection<C> {
 ///
 #[derive(SystemParam)]
 pub struct CrossComponentCollectionMut<C: Component + 'static> {
-    component_vec: Vec<(Entity, OwnedRwLockWriteGuard<C>)>,
+    component_vec: Vec<MutComponentPtr<C>>,
+
+    /// Borrows on the component storages, released when the
+    /// collection is dropped
+    _borrows: ComponentBorrows,
 }
 
 impl<C: Component + 'static> SystemParam for CrossComponentCollectionMut<C> {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>)
+    fn initialise(world: &World, state: &mut SystemState) -> (Option<InitError>, Option<Self>)
     where
         Self: Sized,
     {
-        let component_vec = match (*world).get_all_component_locks_mut::<C>() {
+        let mut borrows = ComponentBorrows::new();
+        let component_vec = match (*world).get_all_components_mut::<C>(state, &mut borrows) {
             Some(x) => {
                 if x.len() < 2 {
                     // This type of system should not execute
@@ -183,7 +188,13 @@ impl<C: Component + 'static> SystemParam for CrossComponentCollectionMut<C> {
             None => return (Some(InitError {}), None),
         };
 
-        (None, Some(Self { component_vec }))
+        (
+            None,
+            Some(Self {
+                component_vec,
+                _borrows: borrows,
+            }),
+        )
     }
 
     fn get_resource_access_type() -> hashbrown::HashSet<std::any::TypeId> {
@@ -192,7 +203,7 @@ impl<C: Component + 'static> SystemParam for CrossComponentCollectionMut<C> {
         hash_set
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: std::any::TypeId) -> bool {
         true
     }
 }
@@ -256,27 +267,19 @@ impl<C: Component + 'static> CrossComponentCollectionMut<C> {
 /// Private methods for [CrossComponentCollectionMut]
 impl<C: Component + 'static> CrossComponentCollectionMut<C> {
     fn execute_handler_on_component_arr<Func, FilterFunc>(
-        components: &mut Vec<(Entity, OwnedRwLockWriteGuard<C>)>,
+        components: &mut [MutComponentPtr<C>],
         mut execution_func: Func,
         mut filter_func: FilterFunc,
     ) where
         Func: FnMut(MutComponentRefHandle<C>, MutComponentRefHandle<C>),
         FilterFunc: FnMut(MutComponentRefHandle<C>) -> bool,
     {
-        let mut component_vec: Vec<&mut (Entity, OwnedRwLockWriteGuard<C>)> = components
-            .iter_mut()
-            .filter_map(|tuple| {
-                let handle = MutComponentRefHandle {
-                    entity_id: tuple.0,
-                    inner: &mut tuple.1,
-                };
-
-                if !filter_func(handle) {
-                    return None;
-                }
-                Some(tuple)
-            })
-            .collect();
+        let mut component_vec: Vec<&mut MutComponentPtr<C>> = vec![];
+        for raw in components.iter_mut() {
+            if filter_func(Self::get_ref_handle(raw)) {
+                component_vec.push(raw);
+            }
+        }
 
         for i in 0..component_vec.len() {
             for j in (i + 1)..component_vec.len() {
@@ -284,18 +287,21 @@ impl<C: Component + 'static> CrossComponentCollectionMut<C> {
                 let (first, last) = mut_slice.split_at_mut(i + 1);
                 let first_len = first.len();
 
-                let a = MutComponentRefHandle {
-                    entity_id: first[first.len() - 1].0,
-                    inner: &mut first[first.len() - 1].1,
-                };
-
-                let b = MutComponentRefHandle {
-                    entity_id: last[j - first_len].0,
-                    inner: &mut last[j - first_len].1,
-                };
+                let a = Self::get_ref_handle(first[first_len - 1]);
+                let b = Self::get_ref_handle(last[j - first_len]);
 
                 (execution_func)(a, b);
             }
         }
     }
+
+    fn get_ref_handle(raw: &mut MutComponentPtr<C>) -> MutComponentRefHandle<'_, C> {
+        // @SAFETY: The collection holds the mutable borrows
+        //      for as long as it is borrowed
+        let inner = unsafe { raw.mut_handle() };
+        MutComponentRefHandle {
+            entity_id: inner.entity_id,
+            inner,
+        }
+    }
 }
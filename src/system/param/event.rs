@@ -10,6 +10,7 @@ use ecs_macros::SystemParam;
 use crate::{events::Event, world::World};
 
 use super::{InitError, SystemParam};
+use crate::system::state::SystemState;
 
 /// ## Description
 /// A [SystemParam] type used in systems to read events
@@ -50,7 +51,7 @@ impl<E: Event + 'static> EventReader<E> {
 }
 
 impl<E: Event + 'static> SystemParam for EventReader<E> {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>) {
+    fn initialise(world: &World, _state: &mut SystemState) -> (Option<InitError>, Option<Self>) {
         match world.get_event_reader() {
             Some(reader) => (None, Some(reader)),
             None => (Some(InitError {}), None), // Event type is not registered. Skip the system execution
@@ -61,7 +62,7 @@ impl<E: Event + 'static> SystemParam for EventReader<E> {
         hashbrown::HashSet::new()
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: TypeId) -> bool {
         false
     }
 }
@@ -89,7 +90,7 @@ impl EventWriter {
 }
 
 impl SystemParam for EventWriter {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>)
+    fn initialise(world: &World, _state: &mut SystemState) -> (Option<InitError>, Option<Self>)
     where
         Self: Sized,
     {
@@ -100,7 +101,7 @@ impl SystemParam for EventWriter {
         hashbrown::HashSet::new()
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: TypeId) -> bool {
         false
     }
 }
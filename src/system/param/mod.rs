@@ -4,6 +4,7 @@ mod resource;
 mod command_buffer;
 mod component_collection;
 mod cross_components_collection;
+mod removed_components;
 
 pub use query::*;
 pub use event::*;
@@ -11,6 +12,7 @@ pub use resource::*;
 pub use command_buffer::*;
 pub use component_collection::*;
 pub use cross_components_collection::*;
+pub use removed_components::*;
 
 
 use std::{
@@ -21,6 +23,8 @@ use crate::{
     ecs_base::ECSBase, world::World
 };
 
+use super::state::SystemState;
+
 
 #[derive(Debug)]
 pub struct InitError {}
@@ -88,7 +92,10 @@ pub trait SystemParam: ECSBase {
     ///         handles. &mut World makes rust believe the returned guard does not have
     ///         lifetime which lives long enough, so we fool the borrow checker this way
     ///         to make it think that the reference is static.
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>) where Self: Sized;
+    ///
+    /// The [SystemState] of the system owning the parameter is supplied to
+    /// let the parameter access the ticks of the system runs
+    fn initialise(world: &World, state: &mut SystemState) -> (Option<InitError>, Option<Self>) where Self: Sized;
 
     ///
     /// ### Description
@@ -100,9 +107,13 @@ pub trait SystemParam: ECSBase {
     ///
     /// ### Description
     /// 
-    /// Indicated whether or not the resource access method of this 
-    /// [SystemParam] type is mutable in nature or not.
-    fn is_resource_access_mut() -> bool; 
+    /// Indicated whether or not the access of this [SystemParam] type
+    /// to the given resource (one of the [TypeId]s returned by
+    /// [SystemParam::get_resource_access_type]) is mutable in nature or not.
+    ///
+    /// The access is reported per resource, letting the scheduler run
+    /// systems in parallel when they only read the resources they share
+    fn is_resource_access_mut(resource_id: TypeId) -> bool;
 }
 
 
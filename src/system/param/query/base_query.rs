@@ -1,140 +1,704 @@
-use std::any::TypeId;
+use std::{any::TypeId, marker::PhantomData};
 
 
 use crate::{
+    archetype::Archetype,
     component::{
-        handles::{ComponentHandle, MutComponentHandle},
+        borrow::ComponentBorrows,
+        fetch::ComponentFetch,
+        handles::{ComponentPtr, MutComponentPtr, RawHandle},
         Component,
     },
     entity::Entity,
+    system::state::SystemState,
     world::World,
 };
 
+use super::{filter::QueryFilter, query_handles::QueryHandles, query_state::QueryState};
 
 
 
-pub trait SystemQuery {
-    type EntityComponentHandleTuple;
-    type EntityMutComponentHandleTuple;
 
-    /// Gets the type_ids of specified component types in the tuple.
-    /// This vector of type_ids could then be passed into the world
-    /// to get a list of components which have the specified components
-    /// attached to them
+///
+/// ### Description
+///
+/// An element of a [SystemQuery], naming a queried component
+/// along with the kind of access the query has to it:
+///
+/// - `C`: A [Component] type, read by a [`Query`](super::Query) and
+///   written by a [`QueryMut`](super::QueryMut).
+/// - `&C`: The component is read, whichever the query.
+/// - `&mut C`: The component is written, whichever the query.
+/// - [`Option<T>`](Option): The component of the element `T` is supplied
+///   if the entity has it and None otherwise, without affecting the entities
+///   matched by the query.
+/// - [Entity]: The id of the matched entity.
+/// - A tuple of elements, which may itself be nested into another tuple
+///   to query more elements than the tuples are implemented for.
+///
+/// The entities must have the components of the non optional elements
+/// attached to match the query.
+///
+/// This trait is implemented for the [Component] types by the
+/// [`Component`](ecs_macros::Component) derive macro.
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// // Mass is only read, hence the system may run
+/// // in parallel with other systems reading Mass
+/// fn apply_gravity(mut query: Query<(&Mass, &mut Velocity)>) {
+///     for (mass, mut velocity) in query.iter_mut() {
+///         velocity.y -= GRAVITY * mass.0;
+///     }
+/// }
+///
+/// fn count_enemies(query: Query<Entity, With<Enemy>>) {
+///     println!("{} enemies", query.iter().count());
+/// }
+/// ```
+///
+pub trait QueryComponent {
+    type Access: ComponentAccess;
+}
+
+impl<C: Component + 'static> QueryComponent for &C {
+    type Access = ReadAccess<C>;
+}
+
+impl<C: Component + 'static> QueryComponent for &mut C {
+    type Access = WriteAccess<C>;
+}
+
+impl<T: QueryComponent> QueryComponent for Option<T> {
+    type Access = OptionalAccess<T::Access>;
+}
+
+impl QueryComponent for Entity {
+    type Access = EntityAccess;
+}
+
+///
+/// ### Description
+///
+/// Describes how the components of a [QueryComponent] are accessed and
+/// fetched for the entities of an archetype.
+///
+/// The handles depend on whether the owning query is a
+/// [`Query`](super::Query) or a [`QueryMut`](super::QueryMut), which is
+/// given as `query_mutable` where the access depends on it.
+///
+pub trait ComponentAccess {
+    /// Raw handle stored by a [`Query`](super::Query)
+    type Handle: RawHandle;
+
+    /// Raw handle stored by a [`QueryMut`](super::QueryMut)
+    type MutHandle: RawHandle;
+
+    /// State of the element resolved for a single archetype
+    type Fetch<'a>;
+
+    /// Returns the [TypeId]s of the components an entity must
+    /// have attached to match the element
+    fn get_required_component_ids() -> Vec<TypeId>;
+
+    /// Returns the [TypeId]s of the components accessed by the element,
+    /// repeated if a component is accessed more than once
+    fn get_component_ids() -> Vec<TypeId>;
+
+    /// Returns true if the element writes into the component of the given type
+    fn is_component_access_mut(component_id: TypeId, query_mutable: bool) -> bool;
+
+    /// Returns false if no entity of the archetype can match the element
+    fn archetype_may_contain(world: &World, archetype: &Archetype) -> bool;
+
     ///
-    /// We can then iterate through this list of entities to fetch the
-    /// appropriate component handles
-    fn get_query_component_ids() -> Vec<TypeId>;
+    /// ### Description
+    ///
+    /// Resolves the state of the element for the given archetype, borrowing
+    /// the storages read by the element into `borrows` (mutably if the
+    /// element writes into them).
+    ///
+    /// Returns None if any of the borrows fails
+    fn init_fetch<'a>(
+        world: &'a World,
+        archetype: &'a Archetype,
+        borrows: &mut ComponentBorrows,
+        query_mutable: bool,
+    ) -> Option<Self::Fetch<'a>>;
 
-    fn get_components_for_entities(
-        world: &World,
-    ) -> Option<Vec<Self::EntityComponentHandleTuple>>;
+    /// Returns true if the entity matches the element
+    fn matches_entity(fetch: &Self::Fetch<'_>, entity_id: Entity) -> bool;
 
-    fn get_mut_components_for_entities(
-        world: &World,
-    ) -> Option<Vec<Self::EntityMutComponentHandleTuple>>;
+    fn get_handle(fetch: &Self::Fetch<'_>, row: usize, entity_id: Entity, state: &SystemState) -> Self::Handle;
 
-    fn get_component_typeid_set() -> hashbrown::HashSet<TypeId>;
+    fn get_mut_handle(fetch: &Self::Fetch<'_>, row: usize, entity_id: Entity, state: &SystemState) -> Self::MutHandle;
 }
 
-macro_rules! query_systems {
+/// Archetype level state of a queried [Component]
+pub struct QueryComponentFetch<'a, C: Component + 'static> {
+    fetch: ComponentFetch<'a, C>,
+}
+
+/// Generates a [ComponentAccess] into a single component type
+macro_rules! component_access {
+    ($(#[$meta: meta])* $access: ident, $handle: ident, $mut_handle: ident, |$query_mutable: ident| $is_mut: expr) => {
+        $(#[$meta])*
+        pub struct $access<C: Component + 'static> {
+            _component: PhantomData<C>,
+        }
+
+        impl<C: Component + 'static> $access<C> {
+            fn is_access_mut($query_mutable: bool) -> bool {
+                $is_mut
+            }
+        }
+
+        impl<C: Component + 'static> ComponentAccess for $access<C> {
+            type Handle = $handle<C>;
+            type MutHandle = $mut_handle<C>;
+            type Fetch<'a> = QueryComponentFetch<'a, C>;
+
+            fn get_required_component_ids() -> Vec<TypeId> {
+                vec![TypeId::of::<C>()]
+            }
+
+            fn get_component_ids() -> Vec<TypeId> {
+                vec![TypeId::of::<C>()]
+            }
+
+            fn is_component_access_mut(component_id: TypeId, query_mutable: bool) -> bool {
+                component_id == TypeId::of::<C>() && Self::is_access_mut(query_mutable)
+            }
+
+            fn archetype_may_contain(world: &World, archetype: &Archetype) -> bool {
+                world.archetype_may_contain::<C>(archetype)
+            }
+
+            fn init_fetch<'a>(
+                world: &'a World,
+                archetype: &'a Archetype,
+                borrows: &mut ComponentBorrows,
+                query_mutable: bool,
+            ) -> Option<Self::Fetch<'a>> {
+                let fetch = world.get_component_fetch::<C>(archetype);
+
+                // If the borrow fails, this means that the storage
+                // is being held by another system.
+                let borrowed = match Self::is_access_mut(query_mutable) {
+                    true => borrows.try_borrow_mut(fetch.borrow_flag()),
+                    false => borrows.try_borrow(fetch.borrow_flag()),
+                };
+                borrowed.then_some(QueryComponentFetch { fetch })
+            }
+
+            fn matches_entity(fetch: &Self::Fetch<'_>, entity_id: Entity) -> bool {
+                // Entities missing a sparse component are skipped
+                fetch.fetch.contains(entity_id)
+            }
+
+            fn get_handle(fetch: &Self::Fetch<'_>, row: usize, entity_id: Entity, state: &SystemState) -> Self::Handle {
+                $handle::new(
+                    fetch.fetch.get_ptr(row, entity_id),
+                    fetch.fetch.get_ticks_ptr(row, entity_id),
+                    entity_id,
+                    state.last_run(),
+                    state.this_run(),
+                )
+            }
+
+            fn get_mut_handle(fetch: &Self::Fetch<'_>, row: usize, entity_id: Entity, state: &SystemState) -> Self::MutHandle {
+                $mut_handle::new(
+                    fetch.fetch.get_ptr(row, entity_id),
+                    fetch.fetch.get_ticks_ptr(row, entity_id),
+                    entity_id,
+                    state.last_run(),
+                    state.this_run(),
+                )
+            }
+        }
+    };
+}
+
+component_access!(
+    /// Access of a bare component type `C`, following the mutability of the query
+    ParamAccess, ComponentPtr, MutComponentPtr, |query_mutable| query_mutable
+);
+
+component_access!(
+    /// Access of a `&C` element, reading the component in any query
+    ReadAccess, ComponentPtr, ComponentPtr, |_query_mutable| false
+);
+
+component_access!(
+    /// Access of a `&mut C` element, writing into the component in any query
+    WriteAccess, MutComponentPtr, MutComponentPtr, |_query_mutable| true
+);
+
+/// Access of an [`Option<T>`](Option) element, delegating to the access of `T`
+pub struct OptionalAccess<A: ComponentAccess> {
+    _access: PhantomData<A>,
+}
+
+impl<A: ComponentAccess> ComponentAccess for OptionalAccess<A> {
+    type Handle = Option<A::Handle>;
+    type MutHandle = Option<A::MutHandle>;
+
+    /// None for the archetypes whose entities can not match the inner element
+    type Fetch<'a> = Option<A::Fetch<'a>>;
+
+    fn get_required_component_ids() -> Vec<TypeId> {
+        vec![]
+    }
+
+    fn get_component_ids() -> Vec<TypeId> {
+        A::get_component_ids()
+    }
+
+    fn is_component_access_mut(component_id: TypeId, query_mutable: bool) -> bool {
+        A::is_component_access_mut(component_id, query_mutable)
+    }
+
+    fn archetype_may_contain(_world: &World, _archetype: &Archetype) -> bool {
+        true
+    }
+
+    fn init_fetch<'a>(
+        world: &'a World,
+        archetype: &'a Archetype,
+        borrows: &mut ComponentBorrows,
+        query_mutable: bool,
+    ) -> Option<Self::Fetch<'a>> {
+        if !A::archetype_may_contain(world, archetype) {
+            return Some(None);
+        }
+        A::init_fetch(world, archetype, borrows, query_mutable).map(Some)
+    }
+
+    fn matches_entity(_fetch: &Self::Fetch<'_>, _entity_id: Entity) -> bool {
+        true
+    }
+
+    fn get_handle(fetch: &Self::Fetch<'_>, row: usize, entity_id: Entity, state: &SystemState) -> Self::Handle {
+        fetch
+            .as_ref()
+            .filter(|fetch| A::matches_entity(fetch, entity_id))
+            .map(|fetch| A::get_handle(fetch, row, entity_id, state))
+    }
+
+    fn get_mut_handle(fetch: &Self::Fetch<'_>, row: usize, entity_id: Entity, state: &SystemState) -> Self::MutHandle {
+        fetch
+            .as_ref()
+            .filter(|fetch| A::matches_entity(fetch, entity_id))
+            .map(|fetch| A::get_mut_handle(fetch, row, entity_id, state))
+    }
+}
+
+/// Access of an [Entity] element, supplying the id of the
+/// matched entity without accessing any component
+pub struct EntityAccess;
+
+impl ComponentAccess for EntityAccess {
+    type Handle = Entity;
+    type MutHandle = Entity;
+    type Fetch<'a> = ();
+
+    fn get_required_component_ids() -> Vec<TypeId> {
+        vec![]
+    }
+
+    fn get_component_ids() -> Vec<TypeId> {
+        vec![]
+    }
+
+    fn is_component_access_mut(_component_id: TypeId, _query_mutable: bool) -> bool {
+        false
+    }
+
+    fn archetype_may_contain(_world: &World, _archetype: &Archetype) -> bool {
+        true
+    }
+
+    fn init_fetch<'a>(
+        _world: &'a World,
+        _archetype: &'a Archetype,
+        _borrows: &mut ComponentBorrows,
+        _query_mutable: bool,
+    ) -> Option<Self::Fetch<'a>> {
+        Some(())
+    }
+
+    fn matches_entity(_fetch: &Self::Fetch<'_>, _entity_id: Entity) -> bool {
+        true
+    }
+
+    fn get_handle(_fetch: &Self::Fetch<'_>, _row: usize, entity_id: Entity, _state: &SystemState) -> Self::Handle {
+        entity_id
+    }
+
+    fn get_mut_handle(_fetch: &Self::Fetch<'_>, _row: usize, entity_id: Entity, _state: &SystemState) -> Self::MutHandle {
+        entity_id
+    }
+}
+
+macro_rules! tuple_access {
     ($($param: ident),*) => {
 
         #[allow(non_snake_case)]
-        impl<$($param: Component + 'static),*> SystemQuery for (Entity, $($param),*) {
-            type EntityComponentHandleTuple = (Entity, $(ComponentHandle<$param>),*);
-            type EntityMutComponentHandleTuple = (Entity, $(MutComponentHandle<$param>),*);
+        impl<$($param: ComponentAccess),*> ComponentAccess for ($($param,)*) {
+            type Handle = ($($param::Handle,)*);
+            type MutHandle = ($($param::MutHandle,)*);
+            type Fetch<'a> = ($($param::Fetch<'a>,)*);
 
+            fn get_required_component_ids() -> Vec<TypeId> {
+                let mut component_ids = vec![];
+                $(component_ids.extend($param::get_required_component_ids());)*
+                component_ids
+            }
 
-            fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
-                let mut hash_set = hashbrown::HashSet::new();
-                $(hash_set.insert(std::any::TypeId::of::<$param>());)*
-                hash_set
+            fn get_component_ids() -> Vec<TypeId> {
+                let mut component_ids = vec![];
+                $(component_ids.extend($param::get_component_ids());)*
+                component_ids
             }
 
+            fn is_component_access_mut(component_id: TypeId, query_mutable: bool) -> bool {
+                $($param::is_component_access_mut(component_id, query_mutable) ||)* false
+            }
 
-            fn get_query_component_ids(
-            ) -> Vec<TypeId> {
-                vec![$(std::any::TypeId::of::<$param>()),*]
+            fn archetype_may_contain(world: &World, archetype: &Archetype) -> bool {
+                $($param::archetype_may_contain(world, archetype) &&)* true
             }
 
-            fn get_components_for_entities(
-                world: &World,
-            ) -> Option<Vec<Self::EntityComponentHandleTuple>> {
-                // Geting all entities which have the components mentioned in the tuple
-                let entities: hashbrown::HashSet<&Entity> =
-                world.get_entities_with_components::<Self>();
+            fn init_fetch<'a>(
+                world: &'a World,
+                archetype: &'a Archetype,
+                borrows: &mut ComponentBorrows,
+                query_mutable: bool,
+            ) -> Option<Self::Fetch<'a>> {
+                // If a borrow fails, we surrender all acquired borrows
+                // (dropped along with the caller's collection) and return None
+                Some(($($param::init_fetch(world, archetype, borrows, query_mutable)?,)*))
+            }
 
-                // Get the mutable component access for each one of them, and push it to the vec
-                let mut aggregated_vec: Vec<Self::EntityComponentHandleTuple> = vec![];
-                for entity in entities {
+            fn matches_entity(fetch: &Self::Fetch<'_>, entity_id: Entity) -> bool {
+                let ($($param,)*) = fetch;
+                $($param::matches_entity($param, entity_id) &&)* true
+            }
 
-                    // Acquiring Component references with their corressponding entities
-                    let tuple = (
-                        *entity,
-                        $(
-                            match world.get_component_ref_lock::<$param>(*entity) {
-                                Some(x) => ComponentHandle::new(x, *entity),
+            fn get_handle(fetch: &Self::Fetch<'_>, row: usize, entity_id: Entity, state: &SystemState) -> Self::Handle {
+                let ($($param,)*) = fetch;
+                ($($param::get_handle($param, row, entity_id, state),)*)
+            }
 
-                                // If the component fetch fails, this means that either
-                                // component is unavailable, or it has been deleted.
-                                // In this case we surrender all acquired component references
-                                // and return None
-                                None => {
-                                    return None;
-                                }
-                            }
-                        ),*
-                    );
+            fn get_mut_handle(fetch: &Self::Fetch<'_>, row: usize, entity_id: Entity, state: &SystemState) -> Self::MutHandle {
+                let ($($param,)*) = fetch;
+                ($($param::get_mut_handle($param, row, entity_id, state),)*)
+            }
+        }
 
-                    aggregated_vec.push(tuple);
-                }
+        impl<$($param: QueryComponent),*> QueryComponent for ($($param,)*) {
+            type Access = ($($param::Access,)*);
+        }
+    }
+}
 
-                // If all acquisitions were successful, we have successfully
-                // acquire state access into the world for all the required
-                // components. We can finally return
-                Some(aggregated_vec)
-            }
-
-            fn get_mut_components_for_entities(
-                world: &World,
-            ) -> Option<Vec<Self::EntityMutComponentHandleTuple>> {
-                // Geting all entities which have the components mentioned in the tuple
-                let entities: hashbrown::HashSet<&Entity> =
-                world.get_entities_with_components::<Self>();
-
-                // Get the mutable component access for each one of them, and push it to the vec
-                let mut aggregated_vec: Vec<Self::EntityMutComponentHandleTuple> = vec![];
-                for entity in entities {
-
-                    // Acquiring Component references with their corressponding entities
-                    let tuple = (
-                        *entity,
-                        $(
-                            match world.get_component_ref_mut_lock::<$param>(*entity) {
-                                Some(x) => MutComponentHandle::new(x, *entity),
-
-                                // If the component fetch fails, this means that either
-                                // component is unavailable, or it has been deleted.
-                                // In this case we surrender all acquired component references
-                                // and return None
-                                None => {
-                                    return None;
-                                }
-                            }
-                        ),*
-                    );
-
-                    aggregated_vec.push(tuple);
-                }
+ecs_macros::implement_tuples!(tuple_access, 0, 21, F);
+
+///
+/// ### Description
+///
+/// The type of the components fetched by a [`Query`](super::Query) or a
+/// [`QueryMut`](super::QueryMut), implemented for every [QueryComponent]:
+/// a single element such as `&Position` or [Entity], or a (possibly nested)
+/// tuple of elements such as `(Entity, Position, Option<&Velocity>)`.
+///
+pub trait SystemQuery {
+    /// Raw handles stored by a [`Query`](super::Query) for every entity
+    type EntityComponentHandleTuple: RawHandle;
+
+    /// Raw handles stored by a [`QueryMut`](super::QueryMut) for every entity
+    type EntityMutComponentHandleTuple: RawHandle;
+
+    /// Gets the type_ids of specified component types in the query,
+    /// repeated if a component type is specified more than once.
+    fn get_query_component_ids() -> Vec<TypeId>;
+
+    /// Gets the type_ids of the components which the entities must have
+    /// attached to match the query, excluding the optional components
+    fn get_required_component_ids() -> Vec<TypeId>;
+
+    /// Fetches immutable handles into the queried components of all
+    /// entities matching the filter [`F`], out of the archetypes matched
+    /// by `query_state`. The component storages are borrowed into `borrows`,
+    /// which must outlive the returned handles.
+    fn get_components_for_entities<F: QueryFilter>(
+        world: &World,
+        state: &SystemState,
+        query_state: &QueryState,
+        borrows: &mut ComponentBorrows,
+    ) -> Option<QueryHandles<Self::EntityComponentHandleTuple>>;
+
+    /// Fetches mutable handles into the queried components of all
+    /// entities matching the filter [`F`], out of the archetypes matched
+    /// by `query_state`. The component storages are mutably borrowed into
+    /// `borrows`, which must outlive the returned handles.
+    fn get_mut_components_for_entities<F: QueryFilter>(
+        world: &World,
+        state: &SystemState,
+        query_state: &QueryState,
+        borrows: &mut ComponentBorrows,
+    ) -> Option<QueryHandles<Self::EntityMutComponentHandleTuple>>;
+
+    fn get_component_typeid_set() -> hashbrown::HashSet<TypeId>;
+
+    /// Returns true if any element of the query writes into the component
+    /// of the given type, where `query_mutable` is true for a
+    /// [`QueryMut`](super::QueryMut)
+    fn is_component_access_mut(component_id: TypeId, query_mutable: bool) -> bool;
+}
+
+impl<T: QueryComponent> SystemQuery for T {
+    type EntityComponentHandleTuple = <T::Access as ComponentAccess>::Handle;
+    type EntityMutComponentHandleTuple = <T::Access as ComponentAccess>::MutHandle;
+
+    fn get_query_component_ids() -> Vec<TypeId> {
+        T::Access::get_component_ids()
+    }
+
+    fn get_required_component_ids() -> Vec<TypeId> {
+        T::Access::get_required_component_ids()
+    }
+
+    fn get_components_for_entities<F: QueryFilter>(
+        world: &World,
+        state: &SystemState,
+        query_state: &QueryState,
+        borrows: &mut ComponentBorrows,
+    ) -> Option<QueryHandles<Self::EntityComponentHandleTuple>> {
+        fetch_handles::<T::Access, F, _>(world, state, query_state, borrows, false, T::Access::get_handle)
+    }
+
+    fn get_mut_components_for_entities<F: QueryFilter>(
+        world: &World,
+        state: &SystemState,
+        query_state: &QueryState,
+        borrows: &mut ComponentBorrows,
+    ) -> Option<QueryHandles<Self::EntityMutComponentHandleTuple>> {
+        fetch_handles::<T::Access, F, _>(world, state, query_state, borrows, true, T::Access::get_mut_handle)
+    }
+
+    fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
+        T::Access::get_component_ids().into_iter().collect()
+    }
+
+    fn is_component_access_mut(component_id: TypeId, query_mutable: bool) -> bool {
+        T::Access::is_component_access_mut(component_id, query_mutable)
+    }
+}
+
+/// Fetches the handles of the entities matched by the access [`A`] and the
+/// filter [`F`], see [SystemQuery::get_components_for_entities]
+fn fetch_handles<A: ComponentAccess, F: QueryFilter, H: RawHandle>(
+    world: &World,
+    state: &SystemState,
+    query_state: &QueryState,
+    borrows: &mut ComponentBorrows,
+    query_mutable: bool,
+    get_handle: fn(&A::Fetch<'_>, usize, Entity, &SystemState) -> H,
+) -> Option<QueryHandles<H>> {
+    // Get the component access for each entity in the archetypes, and push it to the handles
+    let mut handles = QueryHandles::new();
+    for archetype in world.get_query_archetypes(query_state) {
+        // Skipping archetypes which can not match the filter
+        if !F::matches_archetype(world, archetype) {
+            continue;
+        }
 
-                // If all acquisitions were successful, we have successfully
-                // acquire state access into the world for all the required
-                // components. We can finally return
-                Some(aggregated_vec)
+        // Skipping the disabled entities, unless the filter includes them
+        if !F::includes_disabled() && world.archetype_is_disabled(archetype) {
+            continue;
+        }
+
+        // If a borrow fails, we surrender all acquired borrows
+        // (dropped along with the caller's collection) and return None.
+        // The storages written by the query are borrowed before the filter
+        // reads them, since a filter borrow is satisfied by the exclusive one
+        let fetch = A::init_fetch(world, archetype, borrows, query_mutable)?;
+        let filter_fetch = F::init_fetch(world, archetype, borrows)?;
+
+        for (row, entity) in archetype.entities().iter().enumerate() {
+            if !A::matches_entity(&fetch, *entity) {
+                continue;
             }
+
+            if !F::matches_entity(&filter_fetch, row, *entity, state) {
+                continue;
+            }
+
+            // Acquiring Component handles with their corressponding entities
+            handles.push(get_handle(&fetch, row, *entity, state), *entity);
         }
     }
+
+    // If all acquisitions were successful, we have successfully
+    // acquire state access into the world for all the required
+    // components. We can finally return
+    Some(handles)
 }
 
-ecs_macros::implement_tuples!(query_systems, 0, 20, F);
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::{
+        ecs_base::ECSBase,
+        system::param::{Query, QueryMut, SystemParam, With},
+    };
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Armor(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "sparse")]
+    struct Shield(u32);
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Health>();
+        world.register_component::<Armor>();
+        world.register_component::<Shield>();
+        world
+    }
+
+    /// Runs a system on the world through a new [SystemState]
+    fn run<P: SystemParam, R>(world: &World, system: impl FnOnce(P) -> R) -> Option<R> {
+        let mut state = SystemState::new();
+        state.begin_run(world.increment_change_tick());
+        let (_, param) = P::initialise(world, &mut state);
+        let result = param.map(system);
+        state.end_run();
+        result
+    }
+
+    #[test]
+    fn optional_components_do_not_restrict_the_matches() {
+        let mut world = new_world();
+        world.spawn(Health(1));
+        world.spawn((Health(2), Armor(20)));
+        world.spawn((Health(3), Shield(30)));
+        world.spawn(Armor(40));
+
+        let mut matched = run(&world, |query: Query<(&Health, Option<&Armor>, Option<&Shield>)>| {
+            query
+                .iter()
+                .map(|(health, armor, shield)| (health.0, armor.map(|armor| armor.0), shield.map(|shield| shield.0)))
+                .collect::<Vec<_>>()
+        })
+        .unwrap();
+        matched.sort();
+        assert_eq!(matched, [(1, None, None), (2, Some(20), None), (3, None, Some(30))]);
+    }
+
+    #[test]
+    fn optional_components_can_be_written() {
+        let mut world = new_world();
+        let armored = world.spawn((Health(1), Armor(10)));
+        let bare = world.spawn(Health(2));
+
+        run(&world, |mut query: QueryMut<(&Health, Option<&mut Armor>)>| {
+            for (health, armor) in query.iter_mut() {
+                if let Some(mut armor) = armor {
+                    armor.0 += health.0;
+                }
+            }
+        });
+
+        assert_eq!(world.entity(armored).get::<Armor>(), Some(&Armor(11)));
+        assert!(!world.entity(bare).contains::<Armor>());
+    }
+
+    #[test]
+    fn mixed_tuples_report_per_component_access() {
+        let health = TypeId::of::<Health>();
+        let armor = TypeId::of::<Armor>();
+
+        assert!(!Query::<(&Health, &mut Armor)>::is_resource_access_mut(health));
+        assert!(Query::<(&Health, &mut Armor)>::is_resource_access_mut(armor));
+        assert!(!Query::<(Health, Armor)>::is_resource_access_mut(armor));
+
+        assert!(!QueryMut::<(&Health, Armor)>::is_resource_access_mut(health));
+        assert!(QueryMut::<(&Health, Armor)>::is_resource_access_mut(armor));
+        assert!(QueryMut::<(Entity, (Health, &Armor))>::is_resource_access_mut(health));
+        assert!(!QueryMut::<(Entity, (Health, &Armor))>::is_resource_access_mut(armor));
+    }
+
+    #[test]
+    fn mixed_tuples_write_only_the_mutable_components() {
+        let mut world = new_world();
+        let entity_id = world.spawn((Health(1), Armor(10)));
+
+        run(&world, |mut query: Query<(&Health, &mut Armor)>| {
+            for (health, mut armor) in &mut query {
+                armor.0 += health.0;
+            }
+        });
+
+        assert_eq!(world.entity(entity_id).get::<Health>(), Some(&Health(1)));
+        assert_eq!(world.entity(entity_id).get::<Armor>(), Some(&Armor(11)));
+    }
+
+    #[test]
+    fn queries_need_not_fetch_the_entity() {
+        let mut world = new_world();
+        world.spawn(Health(1));
+        world.spawn((Health(2), Armor(20)));
+        world.spawn(Armor(30));
+
+        let mut healths = run(&world, |query: Query<&Health>| query.iter().map(|health| health.0).collect::<Vec<_>>()).unwrap();
+        healths.sort();
+        assert_eq!(healths, [1, 2]);
+    }
+
+    #[test]
+    fn single_entity_queries_match_the_filter() {
+        let mut world = new_world();
+        world.spawn(Health(1));
+        let armored = world.spawn((Health(2), Armor(20)));
+
+        let entities = run(&world, |query: Query<Entity, With<Armor>>| query.iter().collect::<Vec<_>>()).unwrap();
+        assert_eq!(entities, [armored]);
+    }
+
+    #[test]
+    fn nested_tuples_are_flattened_into_their_elements() {
+        let mut world = new_world();
+        world.spawn(Health(1));
+        let armored = world.spawn((Health(2), Armor(20)));
+
+        let matched = run(&world, |query: Query<(Entity, (&Health, &Armor))>| {
+            query
+                .iter()
+                .map(|(entity_id, (health, armor))| (entity_id, health.0, armor.0))
+                .collect::<Vec<_>>()
+        })
+        .unwrap();
+        assert_eq!(matched, [(armored, 2, 20)]);
+
+        run(&world, |mut query: QueryMut<((Entity, &Health), &mut Armor)>| {
+            for ((_, health), mut armor) in query.iter_mut() {
+                armor.0 += health.0;
+            }
+        });
+        assert_eq!(world.entity(armored).get::<Armor>(), Some(&Armor(22)));
+    }
+}
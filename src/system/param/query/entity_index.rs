@@ -0,0 +1,101 @@
+use std::{error::Error, fmt::Display};
+
+use hashbrown::HashMap;
+
+use crate::entity::Entity;
+
+///
+/// ### Description
+///
+/// Error returned when fetching the handles of
+/// specific entities from a [`Query`](super::Query)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryEntityError {
+    /// The entity does not match the query
+    NoMatch(Entity),
+
+    /// The entity was requested more than once, which would
+    /// hand out aliasing mutable handles
+    AliasedMutability(Entity),
+}
+
+impl Error for QueryEntityError {}
+
+impl Display for QueryEntityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoMatch(entity_id) => write!(f, "Entity [{}] does not match the query", entity_id),
+            Self::AliasedMutability(entity_id) => {
+                write!(f, "Entity [{}] was requested more than once from the query", entity_id)
+            }
+        }
+    }
+}
+
+///
+/// ### Description
+///
+/// Lookup of the position of the handles of every entity matched by a
+/// query, mapping the [`entity index`](Entity::index) of each entity to
+/// the position of its handles.
+///
+/// The lookup is filled in while the handles are fetched, so finding the
+/// handles of an entity never requires scanning through the query. The
+/// size of the lookup follows the number of matched entities rather than
+/// the highest entity index, since the query is rebuilt on every run.
+///
+pub struct QueryEntityIndex {
+    /// Entities of the handles, in the same order as the handles
+    entities: Vec<Entity>,
+
+    /// Entity index to position mapping
+    positions: HashMap<u32, usize>,
+}
+
+impl QueryEntityIndex {
+    pub(crate) fn new() -> Self {
+        Self {
+            entities: vec![],
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Records the entity of the handles pushed at the end of the query
+    pub(crate) fn push(&mut self, entity_id: Entity) {
+        self.positions.insert(entity_id.index(), self.entities.len());
+        self.entities.push(entity_id);
+    }
+
+    /// Returns the position of the handles of the entity, verifying
+    /// the generation of the entity
+    pub(crate) fn get(&self, entity_id: Entity) -> Option<usize> {
+        let position = *self.positions.get(&entity_id.index())?;
+
+        if self.entities[position] != entity_id {
+            return None;
+        }
+        Some(position)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the positions of the handles of the entities, in the same order.
+    ///
+    /// ### Return Value
+    /// [`QueryEntityError`] if any of the entities does not match the query,
+    /// or if an entity is requested more than once
+    pub(crate) fn get_many<const N: usize>(&self, entities: [Entity; N]) -> Result<[usize; N], QueryEntityError> {
+        let mut positions = [0; N];
+
+        for (i, entity_id) in entities.iter().enumerate() {
+            if entities[..i].contains(entity_id) {
+                return Err(QueryEntityError::AliasedMutability(*entity_id));
+            }
+            positions[i] = self.get(*entity_id).ok_or(QueryEntityError::NoMatch(*entity_id))?;
+        }
+
+        Ok(positions)
+    }
+}
@@ -0,0 +1,610 @@
+use std::{any::TypeId, marker::PhantomData};
+
+use crate::{
+    archetype::Archetype,
+    component::{borrow::ComponentBorrows, fetch::ComponentFetch, sparse_set::ComponentSparseSet, Component},
+    entity::Entity,
+    system::state::SystemState,
+    world::World,
+};
+
+///
+/// ### Description
+///
+/// A filter which can be supplied to a [`Query`](super::Query) or
+/// [`QueryMut`](super::QueryMut) as the second generic parameter to
+/// restrict the entities supplied by the query, without fetching any
+/// additional components.
+///
+/// Tuples of filters match the entities which match every filter
+/// in the tuple, while [Or] matches the entities which match any filter.
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// fn on_moved(query: Query<(Entity, Position), Changed<Position>>) {
+///     for (entity, position) in &query {
+///         // Only positions which changed since the last run of the system
+///     }
+/// }
+///
+/// fn living_players(query: Query<(Entity, Position), (With<Player>, Without<Dead>)>) {
+///     ...
+/// }
+/// ```
+///
+pub trait QueryFilter {
+    /// State of the filter resolved for a single archetype
+    type Fetch<'a>;
+
+    /// Returns the [TypeId]s of the components read by the filter
+    fn get_component_typeid_set() -> hashbrown::HashSet<TypeId>;
+
+    ///
+    /// ### Description
+    ///
+    /// Returns false if none of the entities of the archetype can
+    /// match the filter, which allows the query to skip the archetype
+    fn matches_archetype(world: &World, archetype: &Archetype) -> bool;
+
+    ///
+    /// ### Description
+    ///
+    /// Resolves the state of the filter for the given archetype,
+    /// borrowing the storages read by the filter into `borrows`.
+    ///
+    /// Returns None if any of the borrows fails
+    fn init_fetch<'a>(
+        world: &'a World,
+        archetype: &'a Archetype,
+        borrows: &mut ComponentBorrows,
+    ) -> Option<Self::Fetch<'a>>;
+
+    /// Returns true if the entity at the given row of the archetype
+    /// matches the filter
+    fn matches_entity(
+        fetch: &Self::Fetch<'_>,
+        row: usize,
+        entity_id: Entity,
+        state: &SystemState,
+    ) -> bool;
+
+    /// Returns true if the filter lets the query supply the
+    /// [`disabled`](World::disable) entities
+    fn includes_disabled() -> bool {
+        false
+    }
+}
+
+///
+/// ### Description
+///
+/// [QueryFilter] letting the query supply the [`disabled`](World::disable)
+/// entities along with the enabled entities, which are skipped by default
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// fn pool(query: Query<(Entity, Bullet), IncludeDisabled>) {
+///     for (entity, bullet) in &query {
+///         // Every bullet, including the pooled (disabled) bullets
+///     }
+/// }
+/// ```
+///
+pub struct IncludeDisabled;
+
+impl QueryFilter for IncludeDisabled {
+    type Fetch<'a> = ();
+
+    fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
+        hashbrown::HashSet::new()
+    }
+
+    fn matches_archetype(_world: &World, _archetype: &Archetype) -> bool {
+        true
+    }
+
+    fn init_fetch<'a>(
+        _world: &'a World,
+        _archetype: &'a Archetype,
+        _borrows: &mut ComponentBorrows,
+    ) -> Option<Self::Fetch<'a>> {
+        Some(())
+    }
+
+    fn matches_entity(
+        _fetch: &Self::Fetch<'_>,
+        _row: usize,
+        _entity_id: Entity,
+        _state: &SystemState,
+    ) -> bool {
+        true
+    }
+
+    fn includes_disabled() -> bool {
+        true
+    }
+}
+
+///
+/// ### Description
+///
+/// [QueryFilter] matching the entities whose component of type [`C`]
+/// was attached after the last run of the system
+///
+pub struct Added<C: Component + 'static> {
+    _marker: PhantomData<C>,
+}
+
+///
+/// ### Description
+///
+/// [QueryFilter] matching the entities whose component of type [`C`]
+/// was added or mutated after the last run of the system.
+///
+/// A component is considered mutated when a
+/// [`MutComponentHandle`](crate::component::handles::MutComponentHandle)
+/// into it is dereferenced mutably.
+///
+pub struct Changed<C: Component + 'static> {
+    _marker: PhantomData<C>,
+}
+
+/// Archetype level state of the [Added] and [Changed] filters
+pub struct ChangeFilterFetch<'a, C: Component + 'static> {
+    fetch: ComponentFetch<'a, C>,
+}
+
+macro_rules! change_filters {
+    ($($filter: ident => $check: ident),*) => {
+        $(
+            impl<C: Component + 'static> QueryFilter for $filter<C> {
+                type Fetch<'a> = ChangeFilterFetch<'a, C>;
+
+                fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
+                    let mut hash_set = hashbrown::HashSet::new();
+                    hash_set.insert(TypeId::of::<C>());
+                    hash_set
+                }
+
+                fn matches_archetype(world: &World, archetype: &Archetype) -> bool {
+                    world.archetype_may_contain::<C>(archetype)
+                }
+
+                fn init_fetch<'a>(
+                    world: &'a World,
+                    archetype: &'a Archetype,
+                    borrows: &mut ComponentBorrows,
+                ) -> Option<Self::Fetch<'a>> {
+                    let fetch = world.get_component_fetch::<C>(archetype);
+                    if !borrows.try_borrow_filter(fetch.borrow_flag()) {
+                        return None;
+                    }
+                    Some(ChangeFilterFetch { fetch })
+                }
+
+                fn matches_entity(
+                    fetch: &Self::Fetch<'_>,
+                    row: usize,
+                    entity_id: Entity,
+                    state: &SystemState,
+                ) -> bool {
+                    if !fetch.fetch.contains(entity_id) {
+                        return false;
+                    }
+
+                    // @SAFETY: The storage has been borrowed in init_fetch
+                    let ticks = unsafe { &*fetch.fetch.get_ticks_ptr(row, entity_id) };
+                    ticks.$check(state.last_run(), state.this_run())
+                }
+            }
+        )*
+    };
+}
+
+change_filters!(Added => is_added, Changed => is_changed);
+
+///
+/// ### Description
+///
+/// Presence of a component type in the entities of an archetype,
+/// resolved without borrowing the storage of the component
+///
+pub enum ComponentPresence<'a> {
+    /// Every entity of the archetype has the component
+    All,
+
+    /// No entity of the archetype has the component
+    Absent,
+
+    /// The component uses the sparse storage, hence its presence
+    /// is looked up for each entity in its sparse set
+    Sparse(&'a dyn ComponentSparseSet),
+}
+
+impl ComponentPresence<'_> {
+    pub fn contains(&self, entity_id: Entity) -> bool {
+        match self {
+            Self::All => true,
+            Self::Absent => false,
+            Self::Sparse(sparse_set) => sparse_set.contains(entity_id),
+        }
+    }
+}
+
+///
+/// ### Description
+///
+/// [QueryFilter] matching the entities which have a component of type [`C`]
+/// attached, without fetching the component.
+///
+/// Unlike a component of the query, the filter neither borrows the storage
+/// of the component nor declares an access to it, hence a system filtering
+/// with [With] does not conflict with the systems mutating the component.
+///
+pub struct With<C: Component + 'static> {
+    _marker: PhantomData<C>,
+}
+
+///
+/// ### Description
+///
+/// [QueryFilter] matching the entities which do not have a component of
+/// type [`C`] attached. See [With] for the access rules of the filter.
+///
+pub struct Without<C: Component + 'static> {
+    _marker: PhantomData<C>,
+}
+
+impl<C: Component + 'static> QueryFilter for With<C> {
+    type Fetch<'a> = ComponentPresence<'a>;
+
+    fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
+        hashbrown::HashSet::new()
+    }
+
+    fn matches_archetype(world: &World, archetype: &Archetype) -> bool {
+        world.archetype_may_contain::<C>(archetype)
+    }
+
+    fn init_fetch<'a>(
+        world: &'a World,
+        archetype: &'a Archetype,
+        _borrows: &mut ComponentBorrows,
+    ) -> Option<Self::Fetch<'a>> {
+        Some(world.get_component_presence::<C>(archetype))
+    }
+
+    fn matches_entity(
+        fetch: &Self::Fetch<'_>,
+        _row: usize,
+        entity_id: Entity,
+        _state: &SystemState,
+    ) -> bool {
+        fetch.contains(entity_id)
+    }
+}
+
+impl<C: Component + 'static> QueryFilter for Without<C> {
+    type Fetch<'a> = ComponentPresence<'a>;
+
+    fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
+        hashbrown::HashSet::new()
+    }
+
+    fn matches_archetype(world: &World, archetype: &Archetype) -> bool {
+        !matches!(world.get_component_presence::<C>(archetype), ComponentPresence::All)
+    }
+
+    fn init_fetch<'a>(
+        world: &'a World,
+        archetype: &'a Archetype,
+        _borrows: &mut ComponentBorrows,
+    ) -> Option<Self::Fetch<'a>> {
+        Some(world.get_component_presence::<C>(archetype))
+    }
+
+    fn matches_entity(
+        fetch: &Self::Fetch<'_>,
+        _row: usize,
+        entity_id: Entity,
+        _state: &SystemState,
+    ) -> bool {
+        !fetch.contains(entity_id)
+    }
+}
+
+///
+/// ### Description
+///
+/// [QueryFilter] matching the entities which match any of the
+/// filters of the tuple [`T`], e.g. `Or<(Added<Health>, Changed<Health>)>`
+///
+pub struct Or<T> {
+    _marker: PhantomData<T>,
+}
+
+///
+/// ### Description
+///
+/// [QueryFilter] matching the entities which have any of the components
+/// of the tuple [`T`] attached, e.g. `AnyOf<(Player, Enemy)>`.
+/// See [With] for the access rules of the filter.
+///
+pub struct AnyOf<T> {
+    _marker: PhantomData<T>,
+}
+
+macro_rules! or_filters {
+    ($($param: ident),*) => {
+
+        #[allow(non_snake_case)]
+        impl<$($param: QueryFilter),*> QueryFilter for Or<($($param,)*)> {
+            type Fetch<'a> = ($(Option<$param::Fetch<'a>>,)*);
+
+            fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
+                let mut hash_set = hashbrown::HashSet::new();
+                $(hash_set.extend($param::get_component_typeid_set());)*
+                hash_set
+            }
+
+            fn matches_archetype(world: &World, archetype: &Archetype) -> bool {
+                $($param::matches_archetype(world, archetype) ||)* false
+            }
+
+            fn init_fetch<'a>(
+                world: &'a World,
+                archetype: &'a Archetype,
+                borrows: &mut ComponentBorrows,
+            ) -> Option<Self::Fetch<'a>> {
+                // Filters which can not match the archetype are left unresolved,
+                // since their state may not exist for the archetype
+                Some(($(
+                    match $param::matches_archetype(world, archetype) {
+                        true => Some($param::init_fetch(world, archetype, borrows)?),
+                        false => None,
+                    },
+                )*))
+            }
+
+            fn matches_entity(
+                fetch: &Self::Fetch<'_>,
+                row: usize,
+                entity_id: Entity,
+                state: &SystemState,
+            ) -> bool {
+                let ($($param,)*) = fetch;
+                $($param
+                    .as_ref()
+                    .is_some_and(|fetch| $param::matches_entity(fetch, row, entity_id, state)) ||)* false
+            }
+
+            fn includes_disabled() -> bool {
+                $($param::includes_disabled() ||)* false
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<$($param: Component + 'static),*> QueryFilter for AnyOf<($($param,)*)> {
+            type Fetch<'a> = ($(PresenceOf<'a, $param>,)*);
+
+            fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
+                hashbrown::HashSet::new()
+            }
+
+            fn matches_archetype(world: &World, archetype: &Archetype) -> bool {
+                $(world.archetype_may_contain::<$param>(archetype) ||)* false
+            }
+
+            fn init_fetch<'a>(
+                world: &'a World,
+                archetype: &'a Archetype,
+                _borrows: &mut ComponentBorrows,
+            ) -> Option<Self::Fetch<'a>> {
+                Some(($(PresenceOf::<$param>::new(world.get_component_presence::<$param>(archetype)),)*))
+            }
+
+            fn matches_entity(
+                fetch: &Self::Fetch<'_>,
+                _row: usize,
+                entity_id: Entity,
+                _state: &SystemState,
+            ) -> bool {
+                let ($($param,)*) = fetch;
+                $($param.presence.contains(entity_id) ||)* false
+            }
+        }
+    }
+}
+
+/// [ComponentPresence] of the component type [`C`], used to name the
+/// fetch state of the [AnyOf] filter after the types of its components
+pub struct PresenceOf<'a, C> {
+    presence: ComponentPresence<'a>,
+    _marker: PhantomData<C>,
+}
+
+impl<'a, C> PresenceOf<'a, C> {
+    fn new(presence: ComponentPresence<'a>) -> Self {
+        Self {
+            presence,
+            _marker: PhantomData,
+        }
+    }
+}
+
+ecs_macros::implement_tuples!(or_filters, 0, 20, F);
+
+macro_rules! query_filters {
+    ($($param: ident),*) => {
+
+        #[allow(non_snake_case, unused_variables, unused_mut)]
+        impl<$($param: QueryFilter),*> QueryFilter for ($($param,)*) {
+            type Fetch<'a> = ($($param::Fetch<'a>,)*);
+
+            fn get_component_typeid_set() -> hashbrown::HashSet<TypeId> {
+                let mut hash_set = hashbrown::HashSet::new();
+                $(hash_set.extend($param::get_component_typeid_set());)*
+                hash_set
+            }
+
+            fn matches_archetype(world: &World, archetype: &Archetype) -> bool {
+                $($param::matches_archetype(world, archetype) &&)* true
+            }
+
+            fn init_fetch<'a>(
+                world: &'a World,
+                archetype: &'a Archetype,
+                borrows: &mut ComponentBorrows,
+            ) -> Option<Self::Fetch<'a>> {
+                Some(($($param::init_fetch(world, archetype, borrows)?,)*))
+            }
+
+            fn matches_entity(
+                fetch: &Self::Fetch<'_>,
+                row: usize,
+                entity_id: Entity,
+                state: &SystemState,
+            ) -> bool {
+                let ($($param,)*) = fetch;
+                $($param::matches_entity($param, row, entity_id, state) &&)* true
+            }
+
+            fn includes_disabled() -> bool {
+                $($param::includes_disabled() ||)* false
+            }
+        }
+    }
+}
+
+query_filters!();
+ecs_macros::implement_tuples!(query_filters, 0, 20, F);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::{
+        ecs_base::ECSBase,
+        system::param::{Query, QueryMut, SystemParam},
+    };
+
+    #[derive(Component)]
+    struct Health(u32);
+
+    #[derive(Component)]
+    struct Armor;
+
+    #[derive(Component)]
+    #[component(storage = "sparse")]
+    struct Stunned;
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Health>();
+        world.register_component::<Armor>();
+        world.register_component::<Stunned>();
+        world
+    }
+
+    /// Returns the [Health] values of the entities matching the filter
+    fn matched<F: QueryFilter + 'static>(world: &World) -> Vec<u32> {
+        let mut state = SystemState::new();
+        state.begin_run(world.increment_change_tick());
+        let (_, query) = Query::<&Health, F>::initialise(world, &mut state);
+        let mut values: Vec<u32> = query
+            .map(|query| query.iter().map(|health| health.0).collect())
+            .unwrap_or_default();
+
+        values.sort();
+        values
+    }
+
+    /// Runs a system reading the [Health] components matching the filter
+    fn run_reader<F: QueryFilter + 'static>(world: &World, state: &mut SystemState) -> Vec<u32> {
+        state.begin_run(world.increment_change_tick());
+        let (_, query) = Query::<&Health, F>::initialise(world, state);
+        let mut values: Vec<u32> = query
+            .map(|query| query.iter().map(|health| health.0).collect())
+            .unwrap_or_default();
+        state.end_run();
+
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn added_filter_matches_components_added_since_last_run() {
+        let mut world = new_world();
+        let mut reader = SystemState::new();
+        world.spawn(Health(1));
+
+        assert_eq!(run_reader::<Added<Health>>(&world, &mut reader), vec![1]);
+
+        world.spawn(Health(2));
+        assert_eq!(run_reader::<Added<Health>>(&world, &mut reader), vec![2]);
+        assert!(run_reader::<Added<Health>>(&world, &mut reader).is_empty());
+    }
+
+    #[test]
+    fn changed_filter_matches_components_mutated_since_last_run() {
+        let mut world = new_world();
+        let mut reader = SystemState::new();
+        world.spawn(Health(1));
+        world.spawn(Health(2));
+
+        // Newly added components are considered changed
+        assert_eq!(run_reader::<Changed<Health>>(&world, &mut reader), vec![1, 2]);
+        assert!(run_reader::<Changed<Health>>(&world, &mut reader).is_empty());
+
+        let mut writer = SystemState::new();
+        writer.begin_run(world.increment_change_tick());
+        let (_, query) = QueryMut::<&mut Health, ()>::initialise(&world, &mut writer);
+        for mut health in &mut query.expect("The query failed to borrow the components") {
+            if health.0 == 2 {
+                health.0 = 3;
+            }
+        }
+        writer.end_run();
+
+        assert_eq!(run_reader::<Changed<Health>>(&world, &mut reader), vec![3]);
+        assert!(run_reader::<Changed<Health>>(&world, &mut reader).is_empty());
+    }
+
+    #[test]
+    fn presence_filters_match_dense_and_sparse_components() {
+        let mut world = new_world();
+        world.spawn(Health(1));
+        world.spawn((Health(2), Armor));
+        world.spawn((Health(3), Stunned));
+        world.spawn((Health(4), Armor, Stunned));
+
+        assert_eq!(matched::<With<Armor>>(&world), [2, 4]);
+        assert_eq!(matched::<Without<Armor>>(&world), [1, 3]);
+        assert_eq!(matched::<With<Stunned>>(&world), [3, 4]);
+        assert_eq!(matched::<(With<Armor>, Without<Stunned>)>(&world), [2]);
+    }
+
+    #[test]
+    fn or_and_any_of_match_any_of_their_filters() {
+        let mut world = new_world();
+        world.spawn(Health(1));
+        world.spawn((Health(2), Armor));
+        world.spawn((Health(3), Stunned));
+        world.spawn((Health(4), Armor, Stunned));
+
+        assert_eq!(matched::<AnyOf<(Armor, Stunned)>>(&world), [2, 3, 4]);
+        assert_eq!(matched::<Or<(With<Armor>, With<Stunned>)>>(&world), [2, 3, 4]);
+        assert_eq!(matched::<Or<(Without<Armor>, With<Stunned>)>>(&world), [1, 3, 4]);
+        assert!(matched::<(Without<Armor>, Or<(With<Armor>,)>)>(&world).is_empty());
+    }
+}
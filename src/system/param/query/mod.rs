@@ -1,18 +1,42 @@
 pub mod base_query;
+pub mod entity_index;
+pub mod filter;
+pub mod par_iter;
+pub mod query_handles;
+pub mod query_state;
 
 use base_query::SystemQuery;
+use par_iter::{QueryParIter, QueryParIterMut};
+use query_handles::QueryHandles;
+pub use entity_index::QueryEntityError;
+pub use filter::{Added, AnyOf, Changed, IncludeDisabled, Or, QueryFilter, With, Without};
 
 use std::{
-    any::TypeId, slice::{Iter, IterMut}, vec::IntoIter
+    any::TypeId, marker::PhantomData
 };
 
-use crate::ecs_base::ECSBase;
+use crate::{
+    component::{
+        borrow::ComponentBorrows,
+        handles::{HandleIter, HandleIterMut, RawHandle},
+        Component,
+    },
+    ecs_base::ECSBase,
+    entity::Entity,
+    system::state::SystemState,
+};
 use ecs_macros::SystemParam;
 
 use crate::world::World;
 
 use super::{InitError, SystemParam};
 
+/// Raw handles stored by a [Query] for every entity
+type QueryRaw<T> = <T as SystemQuery>::EntityComponentHandleTuple;
+
+/// Raw handles stored by a [QueryMut] for every entity
+type QueryMutRaw<T> = <T as SystemQuery>::EntityMutComponentHandleTuple;
+
 
 
 
@@ -20,53 +44,149 @@ use super::{InitError, SystemParam};
 /// ### Description
 ///
 /// A system parameter structure which could be used to iterate
-/// through [`component`](crate::component::Component)s coupled 
-/// with their [`entity_id`](crate::Entity)
+/// through [`component`](crate::component::Component)s, optionally
+/// coupled with their [`entity_id`](crate::Entity)
+///
+/// The query type [`T`] is a single element such as `&Position` or
+/// [`Entity`](crate::entity::Entity), or a (possibly nested) tuple of
+/// elements such as `(Entity, &Position, Option<&Velocity>)`.
+/// See [`QueryComponent`](base_query::QueryComponent)
 /// 
 /// This parameter allows to find entities with a subset of 
 /// components and mutate the components attached
 /// to the entity
 ///
-/// The struct supplies the component's along with their [`Entity`](crate::entity::Entity)
-/// **if and only if**
-///     - All components specified are attached to the entity_id.
-///     - All components are free for use and not being held by another system for use.
-///     (NOTE: This has a high chance of resulting in a deadlock through mutual starvation)
+/// The struct supplies the components of an entity **if and only if**
+///     - All components specified are attached to the entity_id. Optional
+///     components (`Option<C>`) are supplied as None when not attached.
+///     - The components are read, except for the `&mut C` components. The
+///     access is reported per component to the scheduler.
+///     - The storages of the components are not mutably borrowed by another system.
+///     (NOTE: The scheduler never runs conflicting systems in the same layer,
+///     hence a failed borrow indicates a scheduling error)
+///     - The entity matches the [`filter`](QueryFilter) [`F`], if one is specified.
+///     - The entity is not [`disabled`](World::disable), unless the filter
+///     includes [IncludeDisabled].
 /// 
 /// 
 /// 
 #[derive(SystemParam)]
-pub struct Query<T: SystemQuery> {
-    entity_tuple_vec: Vec<<T as SystemQuery>::EntityComponentHandleTuple>,
+pub struct Query<T: SystemQuery, F: QueryFilter = ()> {
+    /// Handles of the entities matched by the query
+    handles: QueryHandles<QueryRaw<T>>,
+
+    /// Borrows on the component storages, released when the
+    /// query is dropped
+    _borrows: ComponentBorrows,
+
+    _filter: PhantomData<F>,
 }
 
-impl<T: SystemQuery> Query<T> {
-    pub fn iter(&self) -> Iter<'_, <T as SystemQuery>::EntityComponentHandleTuple> {
-        self.entity_tuple_vec.iter()
+impl<T: SystemQuery, F: QueryFilter> Query<T, F> {
+    pub fn iter(&self) -> HandleIter<'_, QueryRaw<T>> {
+        self.handles.iter()
+    }
+
+    /// Iterates mutably through the handles, allowing the
+    /// `&mut C` components of the query to be written into
+    pub fn iter_mut(&mut self) -> HandleIterMut<'_, QueryRaw<T>> {
+        self.handles.iter_mut()
+    }
+
+    /// Iterates through the handles in parallel, see [QueryParIter]
+    pub fn par_iter(&self) -> QueryParIter<'_, QueryRaw<T>>
+    where
+        QueryRaw<T>: Sync,
+    {
+        self.handles.par_iter()
+    }
+
+    /// Iterates mutably through the handles in parallel, see [QueryParIterMut]
+    pub fn par_iter_mut(&mut self) -> QueryParIterMut<'_, QueryRaw<T>>
+    where
+        QueryRaw<T>: Send,
+    {
+        self.handles.par_iter_mut()
+    }
+
+    /// Returns the handles of the entity, if the entity matches the query
+    pub fn get(&self, entity_id: Entity) -> Option<<QueryRaw<T> as RawHandle>::Handle<'_>> {
+        self.handles.get(entity_id)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the component of type [`C`] attached to the entity, if the
+    /// entity matches the query and the query accesses the component.
+    ///
+    /// The component is found through the entity index of the query,
+    /// whichever its position in the query type.
+    pub fn get_component<C: Component + 'static>(&self, entity_id: Entity) -> Option<&C> {
+        self.handles.get_component(entity_id)
+    }
+
+    pub fn get_mut(&mut self, entity_id: Entity) -> Option<<QueryRaw<T> as RawHandle>::MutHandle<'_>> {
+        self.handles.get_mut(entity_id)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the handles of several entities at once, in the same order
+    /// as the entities.
+    ///
+    /// ### Return Value
+    /// [`QueryEntityError`] if any of the entities does not match the query,
+    /// or if an entity is requested more than once
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Result<[<QueryRaw<T> as RawHandle>::MutHandle<'_>; N], QueryEntityError> {
+        self.handles.get_many_mut(entities)
     }
 }
 
 
-impl<T: SystemQuery> IntoIterator for Query<T> {
-    type Item = <T as SystemQuery>::EntityComponentHandleTuple;
+impl<'q, T: SystemQuery, F: QueryFilter> IntoIterator for &'q Query<T, F> {
+    type Item = <QueryRaw<T> as RawHandle>::Handle<'q>;
 
-    type IntoIter = IntoIter<<T as SystemQuery>::EntityComponentHandleTuple>;
+    type IntoIter = HandleIter<'q, QueryRaw<T>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.entity_tuple_vec.into_iter()
+        self.iter()
     }
 }
 
+impl<'q, T: SystemQuery, F: QueryFilter> IntoIterator for &'q mut Query<T, F> {
+    type Item = <QueryRaw<T> as RawHandle>::MutHandle<'q>;
+
+    type IntoIter = HandleIterMut<'q, QueryRaw<T>>;
 
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
 
-impl<T: SystemQuery + 'static> SystemParam for Query<T> {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>)
+
+
+impl<T: SystemQuery + 'static, F: QueryFilter + 'static> SystemParam for Query<T, F> {
+    fn initialise(world: &World, state: &mut SystemState) -> (Option<InitError>, Option<Self>)
     where
         Self: Sized,
     {
-        // SystemQueries
-        if let Some(extracted_tuples) = T::get_components_for_entities(world) {
-            if extracted_tuples.len() == 0 {
+        // The same storage can not be borrowed in conflicting modes
+        if has_conflicting_access::<T>(false) {
+            return (Some(InitError {}), None);
+        }
+
+        let mut borrows = ComponentBorrows::new();
+        // Matching the archetypes created since the last run of the system
+        world.update_query_state::<T>(state.get_query_state_mut::<T>());
+        let query_state = state.get_query_state::<T>().expect("Query initialisation failed: Query state not found");
+
+        if let Some(handles) = T::get_components_for_entities::<F>(world, state, query_state, &mut borrows) {
+            if handles.is_empty() {
                 // There's no point in running the system if its going to run with
                 // no elements in the query.
                 // @NOTE: This could however present a situation where
@@ -76,7 +196,9 @@ impl<T: SystemQuery + 'static> SystemParam for Query<T> {
             (
                 None,
                 Some(Self {
-                    entity_tuple_vec: extracted_tuples,
+                    handles,
+                    _borrows: borrows,
+                    _filter: PhantomData,
                 }),
             )
         } else {
@@ -85,11 +207,11 @@ impl<T: SystemQuery + 'static> SystemParam for Query<T> {
     }
 
     fn get_resource_access_type() -> hashbrown::HashSet<TypeId> {
-        T::get_component_typeid_set()
+        get_query_access_type::<T, F>(false)
     }
 
-    fn is_resource_access_mut() -> bool {
-        false
+    fn is_resource_access_mut(resource_id: TypeId) -> bool {
+        T::is_component_access_mut(resource_id, false)
     }
 }
 
@@ -101,56 +223,148 @@ impl<T: SystemQuery + 'static> SystemParam for Query<T> {
 /// ### Description
 /// 
 /// A system parameter structure which could be used to iterate
-/// through [`component`](crate::component::Component)s coupled 
-/// with their [`entity_id`](crate::Entity)
+/// through [`component`](crate::component::Component)s, optionally
+/// coupled with their [`entity_id`](crate::Entity)
+///
+/// The query type [`T`] is a single element such as `&Position` or
+/// [`Entity`](crate::entity::Entity), or a (possibly nested) tuple of
+/// elements such as `(Entity, &Position, Option<&Velocity>)`.
+/// See [`QueryComponent`](base_query::QueryComponent)
 /// 
 /// This parameter allows to find entities with a subset of 
 /// components and mutate the components attached
 /// to the entity
 /// 
-/// The struct supplies the component's along with their [`Entity`](crate::entity::Entity)
-/// **if and only if**
-///     - All components specified are attached to the entity_id.
-///     - All components are free for use and not being held by another system for use.
-///     (NOTE: This has a high chance of resulting in a deadlock through mutual starvation)
+/// The struct supplies the components of an entity **if and only if**
+///     - All components specified are attached to the entity_id. Optional
+///     components (`Option<C>`) are supplied as None when not attached.
+///     - The components are written into, except for the `&C` components. The
+///     access is reported per component to the scheduler.
+///     - The storages of the components are not mutably borrowed by another system.
+///     (NOTE: The scheduler never runs conflicting systems in the same layer,
+///     hence a failed borrow indicates a scheduling error)
+///     - The entity matches the [`filter`](QueryFilter) [`F`], if one is specified.
+///     - The entity is not [`disabled`](World::disable), unless the filter
+///     includes [IncludeDisabled].
 ///
 /// 
 /// 
 #[derive(SystemParam)]
-pub struct QueryMut<T: SystemQuery> {
-    entity_tuple_vec: Vec<<T as SystemQuery>::EntityMutComponentHandleTuple>,
+pub struct QueryMut<T: SystemQuery, F: QueryFilter = ()> {
+    /// Handles of the entities matched by the query
+    handles: QueryHandles<QueryMutRaw<T>>,
+
+    /// Mutable borrows on the component storages, released when the
+    /// query is dropped
+    _borrows: ComponentBorrows,
+
+    _filter: PhantomData<F>,
 }
 
 
-impl<T: SystemQuery> IntoIterator for QueryMut<T> {
-    type Item = <T as SystemQuery>::EntityMutComponentHandleTuple;
+impl<T: SystemQuery, F: QueryFilter> QueryMut<T, F> {
+    pub fn iter(&self) -> HandleIter<'_, QueryMutRaw<T>> {
+        self.handles.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> HandleIterMut<'_, QueryMutRaw<T>> {
+        self.handles.iter_mut()
+    }
 
-    type IntoIter = IntoIter<<T as SystemQuery>::EntityMutComponentHandleTuple>;
+    /// Iterates through the handles in parallel, see [QueryParIter]
+    pub fn par_iter(&self) -> QueryParIter<'_, QueryMutRaw<T>>
+    where
+        QueryMutRaw<T>: Sync,
+    {
+        self.handles.par_iter()
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.entity_tuple_vec.into_iter()
+    /// Iterates mutably through the handles in parallel, see [QueryParIterMut]
+    pub fn par_iter_mut(&mut self) -> QueryParIterMut<'_, QueryMutRaw<T>>
+    where
+        QueryMutRaw<T>: Send,
+    {
+        self.handles.par_iter_mut()
+    }
+
+    /// Returns the handles of the entity, if the entity matches the query
+    pub fn get(&self, entity_id: Entity) -> Option<<QueryMutRaw<T> as RawHandle>::Handle<'_>> {
+        self.handles.get(entity_id)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the component of type [`C`] attached to the entity, if the
+    /// entity matches the query and the query accesses the component.
+    ///
+    /// The component is found through the entity index of the query,
+    /// whichever its position in the query type.
+    pub fn get_component<C: Component + 'static>(&self, entity_id: Entity) -> Option<&C> {
+        self.handles.get_component(entity_id)
+    }
+
+    pub fn get_mut(&mut self, entity_id: Entity) -> Option<<QueryMutRaw<T> as RawHandle>::MutHandle<'_>> {
+        self.handles.get_mut(entity_id)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the handles of several entities at once, in the same order
+    /// as the entities.
+    ///
+    /// ### Return Value
+    /// [`QueryEntityError`] if any of the entities does not match the query,
+    /// or if an entity is requested more than once
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Result<[<QueryMutRaw<T> as RawHandle>::MutHandle<'_>; N], QueryEntityError> {
+        self.handles.get_many_mut(entities)
     }
 }
 
-impl<T: SystemQuery> QueryMut<T> {
-    pub fn iter(&self) -> Iter<'_, <T as SystemQuery>::EntityMutComponentHandleTuple> {
-        self.entity_tuple_vec.iter()
+
+impl<'q, T: SystemQuery, F: QueryFilter> IntoIterator for &'q QueryMut<T, F> {
+    type Item = <QueryMutRaw<T> as RawHandle>::Handle<'q>;
+
+    type IntoIter = HandleIter<'q, QueryMutRaw<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
+}
+
+impl<'q, T: SystemQuery, F: QueryFilter> IntoIterator for &'q mut QueryMut<T, F> {
+    type Item = <QueryMutRaw<T> as RawHandle>::MutHandle<'q>;
+
+    type IntoIter = HandleIterMut<'q, QueryMutRaw<T>>;
 
-    pub fn iter_mut(&mut self) -> IterMut<'_, <T as SystemQuery>::EntityMutComponentHandleTuple> {
-        self.entity_tuple_vec.iter_mut()
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
 
 
-impl<T: SystemQuery + 'static> SystemParam for QueryMut<T> {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>)
+impl<T: SystemQuery + 'static, F: QueryFilter + 'static> SystemParam for QueryMut<T, F> {
+    fn initialise(world: &World, state: &mut SystemState) -> (Option<InitError>, Option<Self>)
     where
         Self: Sized,
     {
-        if let Some(extracted_tuples) = T::get_mut_components_for_entities(world) {
-            if extracted_tuples.len() == 0 {
+        // The same storage can not be borrowed in conflicting modes
+        if has_conflicting_access::<T>(true) {
+            return (Some(InitError {}), None);
+        }
+
+        let mut borrows = ComponentBorrows::new();
+        // Matching the archetypes created since the last run of the system
+        world.update_query_state::<T>(state.get_query_state_mut::<T>());
+        let query_state = state.get_query_state::<T>().expect("Query initialisation failed: Query state not found");
+
+        if let Some(handles) = T::get_mut_components_for_entities::<F>(world, state, query_state, &mut borrows) {
+            if handles.is_empty() {
                 // There's no point in running the system if its going to run with
                 // no elements in the query.
                 // @NOTE: This could however present a situation where
@@ -160,7 +374,9 @@ impl<T: SystemQuery + 'static> SystemParam for QueryMut<T> {
             (
                 None,
                 Some(Self {
-                    entity_tuple_vec: extracted_tuples,
+                    handles,
+                    _borrows: borrows,
+                    _filter: PhantomData,
                 }),
             )
         } else {
@@ -169,10 +385,119 @@ impl<T: SystemQuery + 'static> SystemParam for QueryMut<T> {
     }
 
     fn get_resource_access_type() -> hashbrown::HashSet<TypeId> {
-        T::get_component_typeid_set()
+        get_query_access_type::<T, F>(true)
+    }
+
+    fn is_resource_access_mut(resource_id: TypeId) -> bool {
+        T::is_component_access_mut(resource_id, true)
+    }
+}
+
+
+/// Returns the [TypeId]s of the components accessed by a query and its filter,
+/// where `query_mutable` is true for a [QueryMut]
+fn get_query_access_type<T: SystemQuery, F: QueryFilter>(query_mutable: bool) -> hashbrown::HashSet<TypeId> {
+    if has_conflicting_access::<T>(query_mutable) {
+        panic!("Internal dependency error found: Query contains duplicate component types with mutable access");
+    }
+
+    let mut type_set: hashbrown::HashSet<TypeId> = T::get_query_component_ids().into_iter().collect();
+    type_set.extend(F::get_component_typeid_set());
+    type_set
+}
+
+/// Returns true if a component type written into by the query appears more
+/// than once in it, which would hand out aliasing handles into the same
+/// component of an entity
+fn has_conflicting_access<T: SystemQuery>(query_mutable: bool) -> bool {
+    let mut type_set = hashbrown::HashSet::new();
+    T::get_query_component_ids()
+        .into_iter()
+        .any(|component_id| !type_set.insert(component_id) && T::is_component_access_mut(component_id, query_mutable))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::Component;
+
+    use super::*;
+
+    #[derive(Component)]
+    struct Health(u32);
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Health>();
+        world
+    }
+
+    #[test]
+    fn changed_filter_on_written_component() {
+        let world = {
+            let mut world = new_world();
+            world.spawn(Health(10));
+            world
+        };
+        let mut state = SystemState::new();
+
+        state.begin_run(world.increment_change_tick());
+        let (error, query) = QueryMut::<&mut Health, Changed<Health>>::initialise(&world, &mut state);
+        assert!(error.is_none());
+        for mut health in &mut query.expect("The query failed to borrow the components") {
+            health.0 -= 1;
+        }
+        state.end_run();
+
+        // The component was last changed by the previous run
+        state.begin_run(world.increment_change_tick());
+        let (error, query) = QueryMut::<&mut Health, Changed<Health>>::initialise(&world, &mut state);
+        assert!(error.is_some() && query.is_none());
+    }
+
+    #[test]
+    fn get_many_mut_rejects_duplicate_entities() {
+        let mut world = new_world();
+        let first = world.spawn(Health(1));
+        let second = world.spawn(Health(2));
+        let mut state = SystemState::new();
+
+        state.begin_run(world.increment_change_tick());
+        let (_, query) = QueryMut::<&mut Health, ()>::initialise(&world, &mut state);
+        let mut query = query.expect("The query failed to borrow the components");
+
+        assert_eq!(
+            query.get_many_mut([first, first]).err(),
+            Some(QueryEntityError::AliasedMutability(first))
+        );
+
+        let [mut a, mut b] = query.get_many_mut([second, first]).unwrap();
+        std::mem::swap(&mut a.0, &mut b.0);
+        assert_eq!((a.0, b.0), (1, 2));
+        state.end_run();
     }
 
-    fn is_resource_access_mut() -> bool {
-        true
+    #[test]
+    fn conflicting_component_access_fails_initialisation() {
+        let world = {
+            let mut world = new_world();
+            world.spawn(Health(10));
+            world
+        };
+        let mut state = SystemState::new();
+
+        state.begin_run(world.increment_change_tick());
+        let (error, query) = QueryMut::<(&Health, &mut Health), ()>::initialise(&world, &mut state);
+        assert!(error.is_some() && query.is_none());
+
+        let (error, query) = Query::<(&mut Health, &Health), ()>::initialise(&world, &mut state);
+        assert!(error.is_some() && query.is_none());
+
+        // Repeated reads do not conflict
+        let (error, query) = Query::<(&Health, &Health), ()>::initialise(&world, &mut state);
+        assert!(error.is_none() && query.is_some());
+        state.end_run();
     }
 }
@@ -0,0 +1,191 @@
+use rayon::prelude::*;
+
+use crate::component::handles::RawHandle;
+
+/// Number of batches handed out to every thread of the pool when no batch
+/// size is specified, letting idle threads pick up the remaining batches
+const BATCHES_PER_THREAD: usize = 4;
+
+/// Returns the batch size used to process the given number of handles
+fn get_batch_size(batch_size: Option<usize>, len: usize) -> usize {
+    batch_size.unwrap_or_else(|| usize::max(1, len.div_ceil(rayon::current_num_threads() * BATCHES_PER_THREAD)))
+}
+
+///
+/// ### Description
+///
+/// Parallel iterator over the handles of a query, obtained through
+/// [`Query::par_iter`](super::Query::par_iter) or
+/// [`QueryMut::par_iter`](super::QueryMut::par_iter).
+///
+/// The handles are split into batches, each batch being processed by a
+/// single task of the rayon thread pool.
+///
+/// @NOTE: The tasks are spawned on the pool of the calling thread, which
+///     is the pool executing the system graph layers when called from a
+///     system, hence a single heavy system may use all the threads
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// fn count(query: Query<(Entity, Health)>) {
+///     let alive = AtomicUsize::new(0);
+///     query.par_iter().batch_size(256).for_each(|(_, health)| {
+///         if health.0 > 0 {
+///             alive.fetch_add(1, Ordering::Relaxed);
+///         }
+///     });
+/// }
+/// ```
+///
+pub struct QueryParIter<'q, R: RawHandle> {
+    items: &'q [R],
+    batch_size: Option<usize>,
+}
+
+impl<'q, R: RawHandle + Sync> QueryParIter<'q, R> {
+    pub(crate) fn new(items: &'q [R]) -> Self {
+        Self {
+            items,
+            batch_size: None,
+        }
+    }
+
+    /// Sets the number of handles processed by a single task. By default,
+    /// the handles are split evenly across the threads of the pool
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "Query iteration failed: The batch size must be greater than 0");
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Calls the closure on the handles of every entity in parallel,
+    /// returning once all the handles have been processed
+    pub fn for_each<F: Fn(R::Handle<'_>) + Send + Sync>(self, f: F) {
+        let batch_size = get_batch_size(self.batch_size, self.items.len());
+        self.items.par_chunks(batch_size).for_each(|batch| {
+            // @SAFETY: The query holds the borrows for as long as it is borrowed
+            batch.iter().for_each(|raw| f(unsafe { raw.handle() }))
+        });
+    }
+}
+
+///
+/// ### Description
+///
+/// Parallel iterator over the mutable handles of a query, obtained through
+/// [`Query::par_iter_mut`](super::Query::par_iter_mut) or
+/// [`QueryMut::par_iter_mut`](super::QueryMut::par_iter_mut).
+///
+/// See [QueryParIter]
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// fn integrate(mut query: QueryMut<(Entity, Position, Velocity)>) {
+///     query.par_iter_mut().for_each(|(_, mut position, velocity)| {
+///         position.x += velocity.x;
+///     });
+/// }
+/// ```
+///
+pub struct QueryParIterMut<'q, R: RawHandle> {
+    items: &'q mut [R],
+    batch_size: Option<usize>,
+}
+
+impl<'q, R: RawHandle + Send> QueryParIterMut<'q, R> {
+    pub(crate) fn new(items: &'q mut [R]) -> Self {
+        Self {
+            items,
+            batch_size: None,
+        }
+    }
+
+    /// See [QueryParIter::batch_size]
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "Query iteration failed: The batch size must be greater than 0");
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Calls the closure on the handles of every entity in parallel,
+    /// returning once all the handles have been processed
+    pub fn for_each<F: Fn(R::MutHandle<'_>) + Send + Sync>(self, f: F) {
+        let batch_size = get_batch_size(self.batch_size, self.items.len());
+        self.items.par_chunks_mut(batch_size).for_each(|batch| {
+            // @SAFETY: The query holds the borrows for as long as it is borrowed,
+            //      and every raw handle is handed out to a single task
+            batch.iter_mut().for_each(|raw| f(unsafe { raw.mut_handle() }))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        mpsc::channel,
+    };
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::{
+        component::Component,
+        ecs_base::ECSBase,
+        entity::Entity,
+        system::{
+            param::{QueryMut, SystemParam},
+            state::SystemState,
+        },
+        world::World,
+    };
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[test]
+    fn batch_size_defaults_to_batches_per_thread() {
+        let threads = rayon::current_num_threads();
+        assert_eq!(get_batch_size(Some(7), 1000), 7);
+        assert_eq!(get_batch_size(None, 0), 1);
+        assert_eq!(get_batch_size(None, threads * BATCHES_PER_THREAD * 3), 3);
+        assert_eq!(get_batch_size(None, threads * BATCHES_PER_THREAD * 3 + 1), 4);
+    }
+
+    #[test]
+    fn every_handle_is_processed_once() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Health>();
+        let entities: Vec<_> = (0..100).map(|value| world.spawn(Health(value))).collect();
+
+        let mut state = SystemState::new();
+        state.begin_run(world.increment_change_tick());
+        let (_, query) = QueryMut::<&mut Health>::initialise(&world, &mut state);
+        let mut query = query.expect("The query failed to borrow the components");
+
+        query.par_iter_mut().batch_size(8).for_each(|mut health| health.0 += 1);
+
+        let sum = AtomicU32::new(0);
+        query.par_iter().batch_size(3).for_each(|health| {
+            sum.fetch_add(health.0, Ordering::Relaxed);
+        });
+        assert_eq!(sum.into_inner(), (1..=100).sum());
+        drop(query);
+        state.end_run();
+
+        assert_eq!(world.entity(entities[99]).get::<Health>(), Some(&Health(100)));
+    }
+
+    #[test]
+    #[should_panic(expected = "The batch size must be greater than 0")]
+    fn empty_batches_panic() {
+        QueryParIter::<Entity>::new(&[]).batch_size(0);
+    }
+}
@@ -0,0 +1,110 @@
+use std::any::TypeId;
+
+use crate::{
+    component::{
+        handles::{HandleIter, HandleIterMut, RawHandle},
+        Component,
+    },
+    entity::Entity,
+};
+
+use super::{
+    entity_index::{QueryEntityError, QueryEntityIndex},
+    par_iter::{QueryParIter, QueryParIterMut},
+};
+
+///
+/// ### Description
+///
+/// Raw handles of the entities matched by a [`Query`](super::Query) or a
+/// [`QueryMut`](super::QueryMut), along with the [QueryEntityIndex] used
+/// to look up the handles of a specific entity.
+///
+/// @NOTE: The handles are only valid while the borrows of the storages
+///     they point into are held, hence the storage must be owned by the
+///     query holding those borrows, which hands out handles tied to its
+///     own lifetime
+///
+pub struct QueryHandles<R: RawHandle> {
+    handles: Vec<R>,
+
+    /// Position of the handles of every entity
+    index: QueryEntityIndex,
+}
+
+impl<R: RawHandle> QueryHandles<R> {
+    pub(crate) fn new() -> Self {
+        Self {
+            handles: vec![],
+            index: QueryEntityIndex::new(),
+        }
+    }
+
+    /// Records the handles of the entity
+    pub(crate) fn push(&mut self, raw: R, entity_id: Entity) {
+        self.handles.push(raw);
+        self.index.push(entity_id);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> HandleIter<'_, R> {
+        HandleIter::new(&self.handles)
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> HandleIterMut<'_, R> {
+        HandleIterMut::new(&mut self.handles)
+    }
+
+    pub(crate) fn par_iter(&self) -> QueryParIter<'_, R>
+    where
+        R: Sync,
+    {
+        QueryParIter::new(&self.handles)
+    }
+
+    pub(crate) fn par_iter_mut(&mut self) -> QueryParIterMut<'_, R>
+    where
+        R: Send,
+    {
+        QueryParIterMut::new(&mut self.handles)
+    }
+
+    pub(crate) fn get(&self, entity_id: Entity) -> Option<R::Handle<'_>> {
+        let raw = self.handles.get(self.index.get(entity_id)?)?;
+
+        // @SAFETY: The query holds the borrows for as long as it is borrowed
+        Some(unsafe { raw.handle() })
+    }
+
+    pub(crate) fn get_component<C: Component + 'static>(&self, entity_id: Entity) -> Option<&C> {
+        let raw = self.handles.get(self.index.get(entity_id)?)?;
+        let ptr = raw.get_component_ptr(TypeId::of::<C>())?;
+
+        // @SAFETY: The pointer points to a component of type C, and the
+        //      query holds the borrows for as long as it is borrowed
+        Some(unsafe { &*(ptr as *const C) })
+    }
+
+    pub(crate) fn get_mut(&mut self, entity_id: Entity) -> Option<R::MutHandle<'_>> {
+        let raw = self.handles.get_mut(self.index.get(entity_id)?)?;
+
+        // @SAFETY: The query holds the borrows for as long as it is borrowed
+        Some(unsafe { raw.mut_handle() })
+    }
+
+    pub(crate) fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Result<[R::MutHandle<'_>; N], QueryEntityError> {
+        let positions = self.index.get_many(entities)?;
+
+        // @SAFETY: The positions are distinct, since every entity appears
+        //      once in the query, and are within the bounds of the handles.
+        //      The query holds the borrows for as long as it is borrowed
+        let raw_handles = unsafe { self.handles.get_disjoint_unchecked_mut(positions) };
+        Ok(raw_handles.map(|raw| unsafe { raw.mut_handle() }))
+    }
+}
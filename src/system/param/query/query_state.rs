@@ -0,0 +1,140 @@
+use crate::archetype::ArchetypeId;
+
+///
+/// ### Description
+///
+/// Archetypes matched by a query, cached in the [`SystemState`](crate::system::state::SystemState)
+/// of the system owning the query.
+///
+/// Archetypes are never removed from a [`world`](crate::world::World), and the
+/// component set of an archetype never changes. Entities gaining or losing
+/// components are moved between archetypes instead. Hence the state only
+/// needs to match the archetypes created since its last update, and
+/// initialising the query only visits the archetypes it matched.
+///
+pub struct QueryState {
+    /// Number of archetypes of the world which were matched against the query
+    archetype_generation: usize,
+
+    /// Archetypes storing every required dense component of the query
+    matched_archetypes: Vec<ArchetypeId>,
+}
+
+impl QueryState {
+    pub(crate) fn new() -> Self {
+        Self {
+            archetype_generation: 0,
+            matched_archetypes: vec![],
+        }
+    }
+
+    pub(crate) fn archetype_generation(&self) -> usize {
+        self.archetype_generation
+    }
+
+    pub(crate) fn matched_archetypes(&self) -> &[ArchetypeId] {
+        &self.matched_archetypes
+    }
+
+    /// Records the newly matched archetypes, along with the
+    /// number of archetypes of the world they were matched from
+    pub(crate) fn extend(&mut self, matched_archetypes: impl Iterator<Item = ArchetypeId>, archetype_generation: usize) {
+        self.matched_archetypes.extend(matched_archetypes);
+        self.archetype_generation = archetype_generation;
+    }
+}
+
+impl Default for QueryState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::{
+        component::Component,
+        ecs_base::ECSBase,
+        system::{
+            param::{Query, SystemParam},
+            state::SystemState,
+        },
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct Health;
+
+    #[derive(Component)]
+    struct Armor;
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Health>();
+        world.register_component::<Armor>();
+        world
+    }
+
+    #[test]
+    fn only_new_archetypes_are_matched() {
+        let mut world = new_world();
+        world.spawn(Health);
+        let mut state = QueryState::new();
+
+        world.update_query_state::<&Health>(&mut state);
+        let generation = state.archetype_generation();
+        let matched = state.matched_archetypes().to_vec();
+        assert!(generation > 0);
+        assert_eq!(matched.len(), 1);
+
+        // Archetypes without the component advance the generation only
+        world.spawn(Armor);
+        world.update_query_state::<&Health>(&mut state);
+        assert_eq!(state.archetype_generation(), generation + 1);
+        assert_eq!(state.matched_archetypes(), matched);
+
+        world.spawn((Health, Armor));
+        world.update_query_state::<&Health>(&mut state);
+        assert_eq!(state.archetype_generation(), generation + 2);
+        assert_eq!(state.matched_archetypes().len(), 2);
+        assert_eq!(state.matched_archetypes()[0], matched[0]);
+    }
+
+    #[test]
+    fn unregistered_components_leave_the_state_unchanged() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Armor>();
+        world.spawn(Armor);
+        let mut state = QueryState::new();
+
+        world.update_query_state::<&Health>(&mut state);
+        assert_eq!(state.archetype_generation(), 0);
+        assert!(state.matched_archetypes().is_empty());
+    }
+
+    #[test]
+    fn archetypes_created_between_runs_are_picked_up() {
+        let mut world = new_world();
+        world.spawn(Health);
+        let mut state = SystemState::new();
+
+        let mut count_entities = |world: &World| {
+            state.begin_run(world.increment_change_tick());
+            let (_, query) = Query::<&Health>::initialise(world, &mut state);
+            let count = query.map_or(0, |query| query.iter().count());
+            state.end_run();
+            count
+        };
+
+        assert_eq!(count_entities(&world), 1);
+        world.spawn((Health, Armor));
+        assert_eq!(count_entities(&world), 2);
+    }
+}
@@ -0,0 +1,158 @@
+use std::{any::TypeId, marker::PhantomData, slice::Iter, vec::IntoIter};
+
+use ecs_macros::SystemParam;
+
+use super::{InitError, SystemParam};
+use crate::{
+    component::Component, ecs_base::ECSBase, entity::Entity, system::state::SystemState,
+    world::World,
+};
+
+///
+/// ### Description
+///
+/// A [SystemParam] type which supplies the [Entity] ids that lost their
+/// component of type [`C`] since the last run of the system, either through
+/// [`component removal`](World::remove_component_from_entity),
+/// [`bundle removal`](World::remove_bundle) or [`entity removal`](World::remove_entity).
+///
+/// This lets systems which keep state outside of the world clean up after
+/// the removed components.
+///
+/// @NOTE: Removals follow the same buffering rules as
+/// [`events`](super::EventReader). A removal stays readable until the end
+/// of the frame after the one it happened in, hence a system which does
+/// not run during that window misses the removal.
+///
+/// If no component of type [`C`] was removed since the last run, the
+/// system execution is skipped.
+///
+#[derive(SystemParam)]
+pub struct RemovedComponents<C: Component + 'static> {
+    entities: Vec<Entity>,
+    _marker: PhantomData<C>,
+}
+
+impl<C: Component + 'static> RemovedComponents<C> {
+    pub fn iter(&self) -> Iter<'_, Entity> {
+        self.entities.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+impl<C: Component + 'static> IntoIterator for RemovedComponents<C> {
+    type Item = Entity;
+
+    type IntoIter = IntoIter<Entity>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entities.into_iter()
+    }
+}
+
+impl<C: Component + 'static> SystemParam for RemovedComponents<C> {
+    fn initialise(world: &World, state: &mut SystemState) -> (Option<InitError>, Option<Self>) {
+        let entities = match world.get_removed_entities::<C>() {
+            Some(removed_entities) => removed_entities.read(state.last_run(), state.this_run()),
+            None => vec![],
+        };
+
+        if entities.is_empty() {
+            // Nothing was removed, do not need to execute system
+            return (Some(InitError {}), None);
+        }
+
+        (
+            None,
+            Some(Self {
+                entities,
+                _marker: PhantomData,
+            }),
+        )
+    }
+
+    // Removals are only recorded outside of system execution,
+    // hence the parameter does not conflict with any world resource
+    fn get_resource_access_type() -> hashbrown::HashSet<TypeId> {
+        hashbrown::HashSet::new()
+    }
+
+    fn is_resource_access_mut(_resource_id: TypeId) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::Component;
+
+    use super::*;
+
+    #[derive(Component)]
+    struct Health;
+
+    #[derive(Component)]
+    struct Armor;
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Health>();
+        world.register_component::<Armor>();
+        world
+    }
+
+    /// Runs a system reading the removed [Health] components
+    fn run_reader(world: &World, state: &mut SystemState) -> Vec<Entity> {
+        state.begin_run(world.increment_change_tick());
+        let (_, removed) = RemovedComponents::<Health>::initialise(world, state);
+        let mut entities: Vec<Entity> = removed.into_iter().flatten().collect();
+        state.end_run();
+
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn removals_are_read_once_per_system() {
+        let mut world = new_world();
+        let mut reader = SystemState::new();
+        let removed = world.spawn((Health, Armor));
+        let bundle_removed = world.spawn((Health, Armor));
+        let despawned = world.spawn(Health);
+        let kept = world.spawn((Health, Armor));
+
+        world.remove_component_from_entity::<Health>(removed);
+        world.remove_bundle::<(Health, Armor)>(bundle_removed);
+        world.remove_entity(despawned);
+        world.remove_component_from_entity::<Armor>(kept);
+
+        assert_eq!(run_reader(&world, &mut reader), [removed, bundle_removed, despawned]);
+        assert!(run_reader(&world, &mut reader).is_empty());
+
+        // Every system reads the removals independently
+        assert_eq!(run_reader(&world, &mut SystemState::new()).len(), 3);
+    }
+
+    #[test]
+    fn removals_are_dropped_after_the_next_frame() {
+        let mut world = new_world();
+        let entity_id = world.spawn(Health);
+        world.remove_component_from_entity::<Health>(entity_id);
+
+        world.update_event_state();
+        assert_eq!(run_reader(&world, &mut SystemState::new()), [entity_id]);
+
+        world.update_event_state();
+        assert!(run_reader(&world, &mut SystemState::new()).is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use ecs_macros::SystemParam;
 use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard};
 
 use super::{InitError, SystemParam};
+use crate::system::state::SystemState;
 use crate::ecs_base::ECSBase;
 use crate::{resource::Resource, world::World};
 use std::{
@@ -33,7 +34,7 @@ where
 }
 
 impl<'a, R: Resource + 'static> SystemParam for ResourceHandle<R> {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>) {
+    fn initialise(world: &World, _state: &mut SystemState) -> (Option<InitError>, Option<Self>) {
         match (*world).get_resource_ref_lock::<R>() {
             (ResourceFetchResult::Success, Some(guard_box)) => (
                 None,
@@ -54,7 +55,7 @@ impl<'a, R: Resource + 'static> SystemParam for ResourceHandle<R> {
         hash_set
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: std::any::TypeId) -> bool {
         false
     }
 }
@@ -77,7 +78,7 @@ where
 }
 
 impl<'a, R: Resource + 'static> SystemParam for MutResourceHandle<R> {
-    fn initialise(world: &World) -> (Option<InitError>, Option<Self>) {
+    fn initialise(world: &World, _state: &mut SystemState) -> (Option<InitError>, Option<Self>) {
         match (*world).get_resource_mut_lock::<R>() {
             (ResourceFetchResult::Success, Some(guard_box)) => (
                 None,
@@ -98,7 +99,7 @@ impl<'a, R: Resource + 'static> SystemParam for MutResourceHandle<R> {
         hash_set
     }
 
-    fn is_resource_access_mut() -> bool {
+    fn is_resource_access_mut(_resource_id: std::any::TypeId) -> bool {
         true
     }
 }
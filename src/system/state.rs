@@ -0,0 +1,74 @@
+use std::any::TypeId;
+
+use hashbrown::HashMap;
+
+use crate::{component::tick::Tick, system::param::query_state::QueryState};
+
+///
+/// ### Description
+///
+/// State of a [`system`](crate::system::System) which persists across
+/// its runs, supplied to every [`system param`](crate::system::param::SystemParam)
+/// of the system during initialisation.
+///
+/// The state records the [`ticks`](Tick) of the runs of the system,
+/// which are used to detect the components that were added or changed
+/// since the system last ran, along with the [QueryState]s of the
+/// queries of the system.
+///
+pub struct SystemState {
+    /// Change tick of the world when the system last ran
+    last_run: Tick,
+
+    /// Change tick of the world for the current run of the system
+    this_run: Tick,
+
+    /// Archetypes matched by the queries of the system,
+    /// keyed by the [TypeId] of the query tuple
+    query_states: HashMap<TypeId, QueryState>,
+}
+
+impl SystemState {
+    pub fn new() -> Self {
+        Self {
+            last_run: Tick::default(),
+            this_run: Tick::default(),
+            query_states: HashMap::new(),
+        }
+    }
+
+    pub fn last_run(&self) -> Tick {
+        self.last_run
+    }
+
+    pub fn this_run(&self) -> Tick {
+        self.this_run
+    }
+
+    /// Returns the state of the query with the tuple [`Q`],
+    /// creating an empty state on the first access
+    pub(crate) fn get_query_state_mut<Q: 'static>(&mut self) -> &mut QueryState {
+        self.query_states.entry(TypeId::of::<Q>()).or_default()
+    }
+
+    pub(crate) fn get_query_state<Q: 'static>(&self) -> Option<&QueryState> {
+        self.query_states.get(&TypeId::of::<Q>())
+    }
+
+    /// Marks the beginning of a new run of the system at the given tick
+    pub(crate) fn begin_run(&mut self, this_run: Tick) {
+        self.this_run = this_run;
+    }
+
+    /// Marks the current run of the system as complete, changes
+    /// after this run are detected in the next run of the system
+    pub(crate) fn end_run(&mut self) {
+        self.last_run = self.this_run;
+    }
+}
+
+impl Default for SystemState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
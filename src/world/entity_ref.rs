@@ -0,0 +1,235 @@
+use crate::{
+    component::{bundle::Bundle, component_manager::ComponentInfo, Component},
+    entity::Entity,
+    world::World,
+};
+
+///
+/// ### Description
+///
+/// Read only access to a single entity of a [World] and the components
+/// attached to it, obtained through [World::entity].
+///
+/// Meant for the code running outside of the systems, such as tools,
+/// tests and exclusive code paths.
+///
+/// @SAFETY: The component storages are not borrowed, hence the accessor
+///     must only be used while no system is running on the world
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// let entity = world.entity(entity_id);
+/// if let Some(position) = entity.get::<Position>() {
+///     println!("{} is at {:?}", entity.id(), position);
+/// }
+/// ```
+///
+pub struct EntityRef<'w> {
+    world: &'w World,
+    entity_id: Entity,
+}
+
+impl<'w> EntityRef<'w> {
+    pub(crate) fn new(world: &'w World, entity_id: Entity) -> Self {
+        Self { world, entity_id }
+    }
+
+    pub fn id(&self) -> Entity {
+        self.entity_id
+    }
+
+    /// Returns the component of type [`C`] attached to the entity
+    pub fn get<C: Component + 'static>(&self) -> Option<&'w C> {
+        self.world.get_component::<C>(self.entity_id)
+    }
+
+    /// Returns true if a component of type [`C`] is attached to the entity
+    pub fn contains<C: Component + 'static>(&self) -> bool {
+        self.world.has_component::<C>(self.entity_id)
+    }
+
+    /// Returns the information of every component type attached to
+    /// the entity, including the dynamic components
+    pub fn components(&self) -> Vec<&'w ComponentInfo> {
+        self.world.get_entity_component_infos(self.entity_id)
+    }
+}
+
+///
+/// ### Description
+///
+/// Mutable access to a single entity of a [World] and the components
+/// attached to it, obtained through [World::entity_mut].
+///
+/// The mutations are performed through the [World] APIs, hence they
+/// trigger the component hooks and insert the required components.
+///
+/// @NOTE: A hook may remove the entity while it is being mutated. Every
+///     further operation performed through the accessor is then ignored
+///
+/// ---
+///
+/// ### Example:
+///
+/// ```ignore
+/// world
+///     .entity_mut(entity_id)
+///     .insert(Position { x: 0.0, y: 0.0 })
+///     .remove::<Velocity>();
+/// ```
+///
+pub struct EntityMut<'w> {
+    world: &'w mut World,
+    entity_id: Entity,
+}
+
+impl<'w> EntityMut<'w> {
+    pub(crate) fn new(world: &'w mut World, entity_id: Entity) -> Self {
+        Self { world, entity_id }
+    }
+
+    pub fn id(&self) -> Entity {
+        self.entity_id
+    }
+
+    /// Returns the component of type [`C`] attached to the entity
+    pub fn get<C: Component + 'static>(&self) -> Option<&C> {
+        self.world.get_component::<C>(self.entity_id)
+    }
+
+    /// Returns the component of type [`C`] attached to the entity,
+    /// marking the component as changed
+    pub fn get_mut<C: Component + 'static>(&mut self) -> Option<&mut C> {
+        self.world.get_component_mut::<C>(self.entity_id)
+    }
+
+    /// Returns true if a component of type [`C`] is attached to the entity
+    pub fn contains<C: Component + 'static>(&self) -> bool {
+        self.world.has_component::<C>(self.entity_id)
+    }
+
+    /// Returns the information of every component type attached to
+    /// the entity, including the dynamic components
+    pub fn components(&self) -> Vec<&ComponentInfo> {
+        self.world.get_entity_component_infos(self.entity_id)
+    }
+
+    /// Attaches the component to the entity, replacing the component of
+    /// the same type if any. See [World::insert_component]
+    pub fn insert<C: Component + 'static>(&mut self, component: C) -> &mut Self {
+        self.world.insert_component(self.entity_id, component);
+        self
+    }
+
+    /// Attaches all the components of the [Bundle] to the entity.
+    /// See [World::insert_bundle]
+    pub fn insert_bundle<B: Bundle>(&mut self, bundle: B) -> &mut Self {
+        self.world.insert_bundle(self.entity_id, bundle);
+        self
+    }
+
+    /// Removes the component of type [`C`] from the entity
+    pub fn remove<C: Component + 'static>(&mut self) -> &mut Self {
+        self.world.remove_component_from_entity::<C>(self.entity_id);
+        self
+    }
+
+    /// Removes the entity from the world, consuming the accessor
+    pub fn despawn(self) {
+        self.world.remove_entity(self.entity_id);
+    }
+
+    /// Returns a read only accessor to the entity
+    pub fn as_readonly(&self) -> EntityRef<'_> {
+        EntityRef::new(self.world, self.entity_id)
+    }
+
+    pub fn world(&self) -> &World {
+        self.world
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the world of the entity, allowing operations on other
+    /// entities through the accessor.
+    ///
+    /// @NOTE: The entity may not be alive anymore after the world is mutated
+    pub fn world_mut(&mut self) -> &mut World {
+        self.world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::ecs_base::ECSBase;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Position(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "sparse")]
+    struct Velocity(u32);
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Position>();
+        world.register_component::<Velocity>();
+        world
+    }
+
+    #[test]
+    fn entity_ref_reads_the_components() {
+        let mut world = new_world();
+        let entity_id = world.spawn((Position(1), Velocity(2)));
+
+        let entity = world.entity(entity_id);
+        assert_eq!(entity.id(), entity_id);
+        assert_eq!(entity.get::<Position>(), Some(&Position(1)));
+        assert_eq!(entity.get::<Velocity>(), Some(&Velocity(2)));
+        assert!(entity.contains::<Velocity>());
+
+        let mut names: Vec<&str> = entity.components().iter().map(|info| info.name()).collect();
+        names.sort();
+        assert_eq!(names, ["Position", "Velocity"]);
+    }
+
+    #[test]
+    fn entity_mut_mutates_through_the_world() {
+        let mut world = new_world();
+        let entity_id = world.spawn(Position(1));
+
+        world
+            .entity_mut(entity_id)
+            .insert(Velocity(2))
+            .remove::<Position>()
+            .insert_bundle(Position(3));
+        world.entity_mut(entity_id).get_mut::<Velocity>().unwrap().0 += 1;
+
+        let entity = world.entity(entity_id);
+        assert_eq!(entity.get::<Position>(), Some(&Position(3)));
+        assert_eq!(entity.get::<Velocity>(), Some(&Velocity(3)));
+
+        world.entity_mut(entity_id).despawn();
+        assert!(world.get_entity(entity_id).is_none());
+        assert!(world.get_entity_mut(entity_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "is not alive")]
+    fn accessing_dead_entities_panics() {
+        let mut world = new_world();
+        let entity_id = world.spawn(Position(1));
+        world.remove_entity(entity_id);
+        world.entity(entity_id);
+    }
+}
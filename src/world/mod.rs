@@ -1,9 +1,11 @@
 pub mod command_type;
+pub mod entity_ref;
 pub(crate) mod unsafe_world;
 
 use std::{
     any::TypeId,
     sync::{
+        atomic::{AtomicU32, Ordering},
         mpsc::Sender,
         Arc,
     },
@@ -16,15 +18,36 @@ use tokio::sync::{
 use hashbrown::HashMap;
 
 use crate::{
+    archetype::{archetype_manager::ArchetypeManager, Archetype},
     component::{
-        component_manager::{ComponentManager, EcsManager},
-        Component,
+        component_manager::{ComponentId, ComponentInfo, ComponentManager},
+        dynamic::{ComponentDescriptor, DynamicSparseSet},
+        borrow::ComponentBorrows,
+        clone::clone_component,
+        bundle::{Bundle, BundleComponentTypes, BundleWriter},
+        fetch::ComponentFetch,
+        handles::{ComponentPtr, MutComponentPtr},
+        hooks::{ComponentHook, ComponentHooks},
+        required::RequiredComponents,
+        removal_buffer::{RemovalBuffer, RemovedEntities},
+        sparse_set::{ComponentSparseSet, SparseSet},
+        tick::Tick,
+        Component, StorageType,
     },
-    entity::{entity_manager::EntityManager, Entity},
+    entity::{
+        disabled::Disabled,
+        entity_manager::{EntityManager, EntityReserver},
+        Entity,
+    },
+    world::entity_ref::{EntityMut, EntityRef},
     events::{event_manager::EventManager, Event},
+    hierarchy::{Children, Parent},
+    prefab::Prefab,
+    relation::{self, Relation, RelationIndex, RelationSource, Relations},
     resource::{Resource, ResourceId},
-    system::param::{
-        EventReader, EventWriter, ResourceFetchResult, base_query::SystemQuery,
+    system::{
+        param::{EventReader, EventWriter, ResourceFetchResult, base_query::SystemQuery, filter::ComponentPresence, query_state::QueryState},
+        state::SystemState,
     },
 };
 
@@ -43,15 +66,39 @@ use crate::{
 ///
 /// It has the following fields
 /// 1. [`entity manager`](World::entity_manager) - This is the entity manager responsible
-/// for generating generational id. For more info on generational id, See [Entity]. This structure generates
-/// ids for entities, which are used by [`component managers`]()
+///    for generating generational id. For more info on generational id, See [Entity]. This structure generates
+///    ids for entities, which are used by [`component managers`]()
+///
+/// 2. [`component manager`](World::component_manager) - The component manager keeps
+///    a record of the component types registered in the world. A component to be handled
+///    in the world must first be registered in the world.
+///
+/// 3. [`archetype manager`](World::archetype_manager) - The archetype manager stores
+///    the [`dense`](StorageType::Dense) components attached to entities in archetype tables,
+///    where all entities with the same set of components share contiguous component columns.
+///    For more info, see [Archetype]
+///
+/// 4. [`sparse sets`](World::sparse_sets) - Storage for components using the
+///    [`sparse`](StorageType::Sparse) storage type, one set per component type.
+///    The world dispatches every component operation to the storage selected
+///    by the component type.
+///
+/// 5. [`change tick`](World::change_tick) - The current tick of the world, which
+///    advances on every system run. Components record the tick at which they were
+///    added and last changed, see [Tick]
+///
+/// 6. [`removed components`](World::removed_components) - Record of the components
+///    removed from entities, which can be read by systems through the
+///    [`RemovedComponents`](crate::system::param::RemovedComponents) parameter
 ///
-/// 2. [`component manager`](World::component_managers) - A component manager is a struct
-/// which implements the EcsManager trait and is responsible for handling affairs related
-/// to a specific type of user defined component. A component to be handled in the world
-/// must first be registered in the world, which in turn creates the appropriate
-/// manager for the component.
+/// 7. [`relation index`](World::relation_index) - Reverse index of the [`relations`](Relation)
+///    between entities, used to apply the [`cleanup policy`](crate::relation::RelationCleanup)
+///    of the relations targeting an entity when the entity is removed
 ///
+/// 8. [`dynamic storages`](World::dynamic_storages) - Storage for the component types
+///    defined at runtime, see [ComponentDescriptor]. The values of these components are
+///    accessed as bytes through their [ComponentId], and written through unsafe methods
+///    since the world can not check the bytes form a valid value
 ///
 ///
 #[allow(dead_code)]
@@ -62,14 +109,34 @@ pub struct World {
     /// Structure responsible for managing entities in the world
     entity_manager: EntityManager,
 
-    /// Component systems based on component types
-    component_managers: HashMap<TypeId, Box<dyn EcsManager>>,
+    /// Record of the component types registered in the world
+    component_manager: ComponentManager,
+
+    /// Archetype tables storing the dense components attached to entities
+    archetype_manager: ArchetypeManager,
+
+    /// Storage of sparse components based on component types
+    sparse_sets: HashMap<TypeId, Box<dyn ComponentSparseSet>>,
+
+    /// Storage of the components defined at runtime based on component ids
+    dynamic_storages: HashMap<ComponentId, DynamicSparseSet>,
+
+    /// The tick assigned to the next system run. Structural changes made
+    /// to the world are recorded at this tick
+    change_tick: AtomicU32,
+
+    /// Double buffered record of the components removed from entities
+    removed_components: RemovalBuffer,
+
+    /// Relations recorded by their target entity, shared with
+    /// the [`RelationSources`](crate::relation::RelationSources) parameters
+    relation_index: Arc<std::sync::RwLock<RelationIndex>>,
 
     // Event processing unit
     event_manager: EventManager,
 
     // command_sender: Sender<CommandFunction>,
-    command_sender: Sender<Box<dyn FnMut(&mut World) -> ()>>,
+    command_sender: Sender<Box<dyn FnMut(&mut World) + Send>>,
 
     // command_receiver: Receiver<CommandFunction>,
     /// Resources present in the world
@@ -78,14 +145,6 @@ pub struct World {
 
 /// Private member implementations
 impl World {
-    ///
-    /// Get the type id of component. Used to fetch the system
-    /// of the component based on it's type id
-    ///
-    fn get_component_type_id<C: Component + 'static>() -> TypeId {
-        TypeId::of::<C>()
-    }
-
     /// ### Description
     ///
     /// Checks if the component has been registered
@@ -93,73 +152,428 @@ impl World {
     /// This validation must be performed before performing operations
     /// on a component
     fn check_component_registered<C: Component + 'static>(&self) -> bool {
-        self.component_managers.contains_key(&TypeId::of::<C>())
+        self.component_manager.is_registered(TypeId::of::<C>())
+    }
+
+    /// Checks if all the components of a bundle have been registered,
+    /// logging the first unregistered component found
+    fn check_bundle_registered(&self, types: &BundleComponentTypes) -> bool {
+        match types
+            .iter()
+            .find(|component| !self.component_manager.is_registered(component.type_id))
+        {
+            Some(component) => {
+                let err_str = format!("Component not registered for use: {}", component.name);
+                log::warn!("{err_str}");
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Checks if the entity is alive, logging a warning for
+    /// the failed operation if it is not
+    fn check_entity_alive(&self, entity_id: Entity, operation: &str) -> bool {
+        if !self.is_alive(entity_id) {
+            let err_str = format!("{operation} failed: Entity [{:?}] is not alive", entity_id);
+            log::warn!("{err_str}");
+            return false;
+        }
+        true
+    }
+
+    /// Returns the information of every statically typed and dynamic
+    /// component attached to the entity
+    fn get_entity_component_infos(&self, entity_id: Entity) -> Vec<&ComponentInfo> {
+        let mut infos: Vec<&ComponentInfo> = self
+            .get_entity_component_ids(entity_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|component_id| self.component_manager.get_info(component_id))
+            .collect();
+
+        infos.extend(
+            self.dynamic_storages
+                .iter()
+                .filter(|(_, storage)| storage.contains(entity_id))
+                .filter_map(|(component_id, _)| self.component_manager.get_info_by_id(*component_id)),
+        );
+        infos.sort_by_key(|info| info.id());
+        infos
+    }
+
+    /// Returns the [TypeId]s of all the dense and sparse components
+    /// attached to the entity, or None if the entity does not exist
+    fn get_entity_component_ids(&self, entity_id: Entity) -> Option<Vec<TypeId>> {
+        let mut component_ids = self.archetype_manager.get_component_ids(entity_id)?.to_vec();
+        component_ids.extend(
+            self.sparse_sets
+                .iter()
+                .filter(|(_, sparse_set)| sparse_set.contains(entity_id))
+                .map(|(component_id, _)| *component_id),
+        );
+        Some(component_ids)
     }
 
     ///
     /// ### Description (Internal):
     ///
-    /// Returns a mutable reference of a [`ComponentSystem`] object
-    /// which is present in the [`EntityManager`] object
+    /// Returns a reference to the component of type [`C`] attached
+    /// to the entity, or None if the entity does not have the component
+    ///
+    /// @SAFETY: The component storages are not borrowed. The function
+    ///     must only be called while no system is running on the world.
+    pub(crate) fn get_component<C: Component + 'static>(&self, entity_id: Entity) -> Option<&C> {
+        if !self.is_alive(entity_id) || !self.check_component_registered::<C>() {
+            return None;
+        }
+
+        let ptr = match C::storage_type() {
+            StorageType::Dense => self.archetype_manager.get_component_ptrs::<C>(entity_id)?.0,
+            StorageType::Sparse => self.get_sparse_set::<C>().get_ptr(entity_id)?,
+        };
+        Some(unsafe { &*ptr })
+    }
+
     ///
-    /// WARNING: The component must be registered in on of the [`component manager`](World::component_managers), otherwise the
-    /// function will result in a panic
+    /// ### Description (Internal):
     ///
-    fn get_manager_mut<C: Component + Sized + 'static>(&mut self) -> &mut ComponentManager<C> {
-        let system = self
-            .component_managers
-            .get_mut(&Self::get_component_type_id::<C>())
-            .unwrap();
-        let system = system
-            .as_any_mut()
-            .downcast_mut::<ComponentManager<C>>()
-            .unwrap();
+    /// Returns a mutable reference to the component of type [`C`] attached
+    /// to the entity and marks the component as changed, or returns None if
+    /// the entity does not have the component
+    pub(crate) fn get_component_mut<C: Component + 'static>(&mut self, entity_id: Entity) -> Option<&mut C> {
+        if !self.is_alive(entity_id) || !self.check_component_registered::<C>() {
+            return None;
+        }
 
-        return system;
+        let (ptr, ticks) = match C::storage_type() {
+            StorageType::Dense => self.archetype_manager.get_component_ptrs::<C>(entity_id)?,
+            StorageType::Sparse => {
+                let sparse_set = self.get_sparse_set::<C>();
+                (sparse_set.get_ptr(entity_id)?, sparse_set.get_ticks_ptr(entity_id)?)
+            }
+        };
+
+        // @SAFETY: The mutable reference to the world guarantees exclusive
+        //      access into the component storages
+        unsafe {
+            (*ticks).set_changed(self.read_change_tick());
+            Some(&mut *ptr)
+        }
+    }
+
+    /// Removes the relations of kind [`R`] held by the source entity from the relation index
+    pub(crate) fn unindex_relations<R: Relation>(&mut self, source: Entity) {
+        let targets: Vec<Entity> = match self.get_component::<Relations<R>>(source) {
+            Some(relations) => relations.targets().collect(),
+            None => return,
+        };
+
+        for target in targets {
+            self.relation_index.write().unwrap().remove(target, source, TypeId::of::<R>());
+        }
     }
 
     ///
     /// ### Description (Internal):
     ///
-    /// Returns an immutable reference of a [`ComponentSystem`] object
-    /// which is present in the [`EntityManager`] object
+    /// Removes the statically typed component with the given [TypeId]
+    /// from the entity, triggering its `on_remove` hook and recording
+    /// the removal
+    fn remove_typed_component(&mut self, entity_id: Entity, component_id: TypeId, storage_type: StorageType) {
+        self.trigger_hooks(entity_id, &[component_id], |hooks| hooks.on_remove);
+
+        // The hooks may have already removed the component
+        let removed = match storage_type {
+            StorageType::Dense => self
+                .archetype_manager
+                .remove_component(entity_id, component_id),
+            StorageType::Sparse => self
+                .sparse_sets
+                .get_mut(&component_id)
+                .unwrap()
+                .remove(entity_id),
+        };
+
+        if removed {
+            let tick = self.read_change_tick();
+            self.removed_components
+                .record(component_id, entity_id, tick);
+        }
+    }
+
+    /// Returns true if a component with the given [TypeId] is attached to the entity
+    fn has_component_id(&self, entity_id: Entity, component_id: TypeId) -> bool {
+        self.archetype_manager.has_component(entity_id, component_id)
+            || self
+                .sparse_sets
+                .get(&component_id)
+                .is_some_and(|sparse_set| sparse_set.contains(entity_id))
+    }
+
+    ///
+    /// ### Description (Internal):
+    ///
+    /// Inserts the [`required components`](RequiredComponents) of the given
+    /// component types which are missing from the entity.
+    ///
+    /// The requirements of the inserted components are inserted in turn,
+    /// since the insertion goes through [World::add_component_to_entity]
+    fn insert_required_components(&mut self, entity_id: Entity, component_ids: &[TypeId]) {
+        for component_id in component_ids {
+            let required_components = match self.component_manager.get_info(*component_id) {
+                Some(info) => info.required_components().clone(),
+                None => continue,
+            };
+
+            for component in required_components.iter() {
+                if !self.has_component_id(entity_id, component.type_id) {
+                    (component.insert)(self, entity_id);
+                }
+            }
+        }
+    }
+
+    ///
+    /// ### Description (Internal):
     ///
-    /// WARNING: The component must be registered on of the [`component manager`](World::component_managers), otherwise the
-    /// function will result in a panic
+    /// Calls the hook selected from the [`hooks`](ComponentHooks) of each
+    /// of the given component types with the entity.
     ///
-    fn get_manager<C: Component + Sized + 'static>(&self) -> &ComponentManager<C> {
-        let system = self
-            .component_managers
-            .get(&Self::get_component_type_id::<C>())
-            .unwrap();
-        let system = system
+    /// The hook is copied out of the component manager before the call,
+    /// since hooks receive a mutable reference to the world
+    fn trigger_hooks(
+        &mut self,
+        entity_id: Entity,
+        component_ids: &[TypeId],
+        select_hook: fn(&ComponentHooks) -> Option<ComponentHook>,
+    ) {
+        for component_id in component_ids {
+            let hook = self
+                .component_manager
+                .get_info(*component_id)
+                .and_then(|info| select_hook(info.hooks()));
+
+            if let Some(hook) = hook {
+                hook(self, entity_id);
+            }
+        }
+    }
+
+    ///
+    /// ### Description (Internal):
+    ///
+    /// Returns a reference of the [SparseSet] storing the components
+    /// of type [`C`].
+    ///
+    /// WARNING: The component must be registered with the sparse storage
+    /// type, otherwise the function will result in a panic
+    ///
+    fn get_sparse_set<C: Component + 'static>(&self) -> &SparseSet<C> {
+        self.sparse_sets
+            .get(&TypeId::of::<C>())
+            .unwrap()
             .as_any()
-            .downcast_ref::<ComponentManager<C>>()
-            .unwrap();
+            .downcast_ref::<SparseSet<C>>()
+            .unwrap()
+    }
+
+    ///
+    /// ### Description (Internal):
+    ///
+    /// Returns a mutable reference of the [SparseSet] storing the
+    /// components of type [`C`].
+    ///
+    /// WARNING: The component must be registered with the sparse storage
+    /// type, otherwise the function will result in a panic
+    ///
+    fn get_sparse_set_mut<C: Component + 'static>(&mut self) -> &mut SparseSet<C> {
+        self.sparse_sets
+            .get_mut(&TypeId::of::<C>())
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<SparseSet<C>>()
+            .unwrap()
+    }
+
+    ///
+    /// ### Description (Internal):
+    ///
+    /// Calls the visitor on every storage holding components of type [`C`],
+    /// along with the entities whose components are stored in it (in storage order).
+    ///
+    /// The [`disabled`](World::disable) entities are left out of the visit.
+    ///
+    /// The visit stops as soon as the visitor returns false, in which
+    /// case false is returned
+    ///
+    fn visit_component_storages<C: Component + 'static>(
+        &self,
+        mut visitor: impl FnMut(ComponentFetch<C>, &[Entity]) -> bool,
+    ) -> bool {
+        if C::storage_type() == StorageType::Sparse {
+            let sparse_set = self.get_sparse_set::<C>();
+
+            // The sparse components are fetched by entity, hence the
+            // entities are not required to be in storage order
+            let entities: Vec<Entity> = sparse_set
+                .entities()
+                .iter()
+                .filter(|entity_id| !self.is_disabled(**entity_id))
+                .copied()
+                .collect();
+            return visitor(ComponentFetch::Sparse(sparse_set), &entities);
+        }
 
-        return system;
+        let component_ids = [TypeId::of::<C>()];
+        let visited_all = self
+            .archetype_manager
+            .get_archetypes_with_components(&component_ids)
+            .filter(|archetype| !self.archetype_is_disabled(archetype))
+            .all(|archetype| visitor(self.get_component_fetch::<C>(archetype), archetype.entities()));
+        visited_all
     }
 }
 
 impl World {
     // pub fn new(command_sender: Sender<CommandFunction>) -> Self {
-    pub fn new(command_sender: Sender<Box<dyn FnMut(&mut World) -> ()>>) -> Self {
-        Self {
+    pub fn new(command_sender: Sender<Box<dyn FnMut(&mut World) + Send>>) -> Self {
+        let mut world = Self {
             active: false,
             cleanup: false,
             entity_manager: EntityManager::new(),
-            component_managers: HashMap::new(),
+            component_manager: ComponentManager::new(),
+            archetype_manager: ArchetypeManager::new(),
+            sparse_sets: HashMap::new(),
+            dynamic_storages: HashMap::new(),
+            // Systems start with a last run tick of 0, hence changes made
+            // before the first run of a system are detected by it
+            change_tick: AtomicU32::new(1),
+            removed_components: RemovalBuffer::new(),
+            relation_index: Arc::new(std::sync::RwLock::new(RelationIndex::new())),
             event_manager: EventManager::new(),
             resources: HashMap::new(),
             command_sender,
-        }
+        };
+
+        // Components of the entity hierarchy are available in every world
+        world.register_component::<Parent>();
+        world.register_component::<Children>();
+        world.register_component::<Disabled>();
+        world
     }
 
     /// ### Description
     ///
     /// Creates an entity in the world and returns its [`id`](Entity)
     pub fn create_entity(&mut self) -> Entity {
-        self.entity_manager.create_entity()
+        self.flush_entities();
+
+        let entity_id = self.entity_manager.create_entity();
+        self.archetype_manager.insert_entity(entity_id);
+        entity_id
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Reserves an [`entity id`](Entity) without mutable access to the world,
+    /// allowing systems running in parallel to obtain real entity ids for
+    /// the commands they defer.
+    ///
+    /// The reserved entity becomes a live (empty) entity once the reserved
+    /// entities are [`flushed`](World::flush_entities), which happens before
+    /// the command buffer is executed. Until then, the entity is not present
+    /// in the world and any operation performed on it is ignored.
+    ///
+    /// ---
+    ///
+    /// ### Example:
+    ///
+    /// ```ignore
+    /// fn spawner(commands: CommandBufferWriter) {
+    ///     let entity_id = commands.reserve_entity();
+    ///     commands.add_command(move |world| {
+    ///         world.add_component_to_entity(entity_id, Position { x: 0.0, y: 0.0 });
+    ///     });
+    /// }
+    /// ```
+    ///
+    pub fn reserve_entity(&self) -> Entity {
+        self.entity_manager.reserve_entity()
+    }
+
+    /// Returns the [EntityReserver] of the world, letting the entities be
+    /// reserved without a reference to the world
+    pub(crate) fn get_entity_reserver(&self) -> Arc<EntityReserver> {
+        self.entity_manager.reserver()
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Materialises the entities [`reserved`](World::reserve_entity)
+    /// since the last flush as empty entities in the world
+    pub fn flush_entities(&mut self) {
+        let archetype_manager = &mut self.archetype_manager;
+        self.entity_manager.flush(|entity_id| archetype_manager.insert_entity(entity_id));
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns a read only [EntityRef] accessor to the entity.
+    ///
+    /// WARNING: Calling this function with an entity which is not
+    ///     alive will result in a panic. See [World::get_entity]
+    ///
+    pub fn entity(&self, entity_id: Entity) -> EntityRef<'_> {
+        match self.get_entity(entity_id) {
+            Some(entity) => entity,
+            None => panic!("Entity access failed: Entity [{}] is not alive", entity_id),
+        }
+    }
+
+    /// Returns a read only [EntityRef] accessor to the entity,
+    /// or None if the entity is not alive
+    pub fn get_entity(&self, entity_id: Entity) -> Option<EntityRef<'_>> {
+        self.is_alive(entity_id)
+            .then(|| EntityRef::new(self, entity_id))
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns a mutable [EntityMut] accessor to the entity.
+    ///
+    /// WARNING: Calling this function with an entity which is not
+    ///     alive will result in a panic. See [World::get_entity_mut]
+    ///
+    pub fn entity_mut(&mut self, entity_id: Entity) -> EntityMut<'_> {
+        match self.get_entity_mut(entity_id) {
+            Some(entity) => entity,
+            None => panic!("Entity access failed: Entity [{}] is not alive", entity_id),
+        }
+    }
+
+    /// Returns a mutable [EntityMut] accessor to the entity,
+    /// or None if the entity is not alive
+    pub fn get_entity_mut(&mut self, entity_id: Entity) -> Option<EntityMut<'_>> {
+        self.is_alive(entity_id)
+            .then(|| EntityMut::new(self, entity_id))
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns true if the entity exists in the world.
+    ///
+    /// Removed entities are never alive again, even when their index is
+    /// reused by a new entity. Every operation performed on an entity which
+    /// is not alive is ignored by the world.
+    ///
+    pub fn is_alive(&self, entity_id: Entity) -> bool {
+        self.entity_manager.is_alive(entity_id)
     }
 
     ///
@@ -168,37 +582,525 @@ impl World {
     /// Removes an entity from the world and deallocates all components
     /// attached to it.
     pub fn remove_entity(&mut self, entity_id: Entity) {
+        self.flush_entities();
+
+        if !self.check_entity_alive(entity_id, "Entity removal") {
+            return;
+        }
+
+        let attached_ids = match self.get_entity_component_ids(entity_id) {
+            Some(attached_ids) => attached_ids,
+            None => return,
+        };
+
+        self.trigger_hooks(entity_id, &attached_ids, |hooks| hooks.on_remove);
+
+        // The hooks may have removed the entity (or changed its components)
+        let component_ids = match self.archetype_manager.get_component_ids(entity_id) {
+            Some(component_ids) => component_ids.to_vec(),
+            None => return,
+        };
+
         // Dispose all components attached to the entity
+        self.archetype_manager.remove_entity(entity_id);
+
+        let tick = self.read_change_tick();
+        for component_id in component_ids {
+            self.removed_components.record(component_id, entity_id, tick);
+        }
+
+        for (component_id, sparse_set) in self.sparse_sets.iter_mut() {
+            if sparse_set.remove(entity_id) {
+                self.removed_components.record(*component_id, entity_id, tick);
+            }
+        }
+
+        for dynamic_storage in self.dynamic_storages.values_mut() {
+            dynamic_storage.remove(entity_id);
+        }
+
+        // The hooks may have reserved entities, which must be materialised
+        // before the slot of the entity is freed
+        self.flush_entities();
+        self.entity_manager.dispose_entity_id(entity_id);
+
+        // Applying the cleanup policies of the relations targeting the entity
+        let relation_sources = self.relation_index.write().unwrap().take(entity_id);
+        for relation_source in relation_sources {
+            (relation_source.on_target_removed)(self, relation_source.source, entity_id);
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Switches the entity off without removing it, by attaching the
+    /// [Disabled] marker to it. The components of a disabled entity are
+    /// kept, but the entity is skipped by the queries and the component
+    /// collections, see [`IncludeDisabled`](crate::system::param::IncludeDisabled).
+    ///
+    /// Disabling a disabled entity has no effect
+    ///
+    pub fn disable(&mut self, entity_id: Entity) {
+        if !self.check_entity_alive(entity_id, "Entity disabling") || self.is_disabled(entity_id) {
+            return;
+        }
+        self.add_component_to_entity(entity_id, Disabled);
+    }
+
+    /// Switches the entity back on, see [World::disable].
+    /// Enabling an enabled entity has no effect
+    pub fn enable(&mut self, entity_id: Entity) {
+        if !self.check_entity_alive(entity_id, "Entity enabling") || !self.is_disabled(entity_id) {
+            return;
+        }
+        self.remove_component_from_entity::<Disabled>(entity_id);
+    }
+
+    /// Returns true if the entity has been [`disabled`](World::disable)
+    pub fn is_disabled(&self, entity_id: Entity) -> bool {
+        self.archetype_manager.has_component(entity_id, TypeId::of::<Disabled>())
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Makes the parent entity the [Parent] of the child entity, detaching
+    /// the child from its previous parent and adding it to the [Children]
+    /// of the new parent.
+    ///
+    /// The call is ignored (with a warning) if either entity does not exist,
+    /// or if the parent is the child itself or one of its descendants.
+    ///
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) {
+        if !self.is_alive(child) || !self.is_alive(parent) {
+            log::warn!("Parent assignment failed: Entity [{:?}] or [{:?}] does not exist in the world", child, parent);
+            return;
+        }
+
+        // Walking the ancestors of the parent to prevent cycles in the hierarchy
+        let mut ancestor = Some(parent);
+        while let Some(entity_id) = ancestor {
+            if entity_id == child {
+                log::warn!("Parent assignment failed: Entity [{:?}] is a descendant of Entity [{:?}]", parent, child);
+                return;
+            }
+            ancestor = self.get_component::<Parent>(entity_id).map(|parent| parent.get());
+        }
+
+        match self.get_component::<Parent>(child).map(|parent| parent.get()) {
+            Some(current) if current == parent => return,
+            Some(_) => self.remove_parent(child),
+            None => {}
+        }
+
+        self.add_component_to_entity(child, Parent(parent));
+        match self.get_component_mut::<Children>(parent) {
+            Some(children) => children.0.push(child),
+            None => self.add_component_to_entity(parent, Children(vec![child])),
+        }
+    }
+
+    /// Adds the child entity to the [Children] of the parent entity,
+    /// see [World::set_parent]
+    pub fn add_child(&mut self, parent: Entity, child: Entity) {
+        self.set_parent(child, parent);
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Detaches the entity from its [Parent], leaving it as a root entity.
+    ///
+    /// Entities without a parent are ignored
+    ///
+    pub fn remove_parent(&mut self, child: Entity) {
+        if self.get_component::<Parent>(child).is_none() {
+            return;
+        }
+
+        // Removing the parent component of the child detaches
+        // the child from the parent through the component hook
+        self.remove_component_from_entity::<Parent>(child);
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Removes the entity along with all of its descendants
+    /// in the hierarchy from the world
+    ///
+    pub fn despawn_recursive(&mut self, entity_id: Entity) {
+        let mut entities = vec![entity_id];
+        let mut index = 0;
+        while index < entities.len() {
+            if let Some(children) = self.get_component::<Children>(entities[index]) {
+                entities.extend(children.iter());
+            }
+            index += 1;
+        }
+
+        // Removing the descendants before their parents, hence the parents
+        // never need to detach their removed children
+        for entity_id in entities.into_iter().rev() {
+            self.remove_entity(entity_id);
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Adds a [Relation] of kind [`R`] from the source entity to the target
+    /// entity, replacing the existing relation of the same kind between the
+    /// two entities, if any.
+    ///
+    /// The relations are stored in the [Relations] component of the source,
+    /// which is registered in the world on first use.
+    ///
+    pub fn add_relation<R: Relation>(&mut self, source: Entity, target: Entity, relation: R) {
+        if !self.is_alive(source) || !self.is_alive(target) {
+            log::warn!("Relation addition failed: Entity [{:?}] or [{:?}] does not exist in the world", source, target);
+            return;
+        }
+
+        if !self.check_component_registered::<Relations<R>>() {
+            self.register_component::<Relations<R>>();
+        }
+
+        match self.get_component_mut::<Relations<R>>(source) {
+            Some(relations) => {
+                // A replaced relation is already recorded in the index
+                if relations.insert(target, relation).is_some() {
+                    return;
+                }
+            }
+            None => self.add_component_to_entity(source, Relations::new(target, relation)),
+        }
+
+        self.relation_index.write().unwrap().insert(
+            target,
+            RelationSource {
+                source,
+                kind: TypeId::of::<R>(),
+                on_target_removed: relation::on_target_removed::<R>,
+            },
+        );
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Removes the [Relation] of kind [`R`] from the source entity to the
+    /// target entity. The [Relations] component is removed from the source
+    /// along with its last relation.
+    ///
+    /// Missing relations are ignored
+    ///
+    pub fn remove_relation<R: Relation>(&mut self, source: Entity, target: Entity) {
+        let is_empty = match self.get_component_mut::<Relations<R>>(source) {
+            Some(relations) => {
+                if relations.remove(target).is_none() {
+                    return;
+                }
+                relations.is_empty()
+            }
+            None => return,
+        };
+
+        self.relation_index.write().unwrap().remove(target, source, TypeId::of::<R>());
+        if is_empty {
+            self.remove_component_from_entity::<Relations<R>>(source);
+        }
+    }
+
+    /// Returns true if the source entity holds a [Relation] of
+    /// kind [`R`] targeting the target entity
+    pub fn has_relation<R: Relation>(&self, source: Entity, target: Entity) -> bool {
+        self.get_component::<Relations<R>>(source)
+            .is_some_and(|relations| relations.contains(target))
+    }
+
+    /// Returns the entities holding a [Relation] of kind [`R`]
+    /// which targets the given entity, see [RelationIndex]
+    pub fn get_relation_sources<R: Relation>(&self, target: Entity) -> Vec<Entity> {
+        self.relation_index.read().unwrap().get_sources(target, TypeId::of::<R>())
+    }
+
+    /// Returns the [RelationIndex] of the world, letting the relation sources
+    /// be looked up without a reference to the world
+    pub(crate) fn get_relation_index(&self) -> Arc<std::sync::RwLock<RelationIndex>> {
+        self.relation_index.clone()
+    }
+
+    ///### Description
+    ///
+    /// Registers a component type in the [`component manager`](World::component_manager)
+    /// and creates the storage for the component if the component uses the
+    /// [`sparse`](StorageType::Sparse) storage type
+    ///
+    /// Components of this type can be attached to generated entities
+    /// only after registering the component type in the [`manager`](World::component_manager).
+    ///
+    /// The [`required components`](RequiredComponents) of the type are
+    /// registered along with it.
+    ///
+    /// WARNING: Registering a component whose required components
+    ///     form a cycle will result in a panic
+    ///
+    pub fn register_component<C: Component + 'static>(&mut self) {
+        if self.component_manager.is_registered(TypeId::of::<C>()) {
+            println!("Component already registered: {}", C::get_name());
+            return;
+        }
+
+        // The requirement graph is validated before anything is registered,
+        // so a failed registration leaves the world untouched
+        let mut required_components = RequiredComponents::new();
+        C::register_required_components(&mut required_components);
+
+        if let Some(cycle) = RequiredComponents::find_cycle(TypeId::of::<C>(), C::get_name(), &required_components) {
+            panic!(
+                "Component registration failed: Required components form a cycle: {}",
+                cycle.join(" -> ")
+            );
+        }
+
+        self.component_manager.register_component::<C>();
+
+        if C::storage_type() == StorageType::Sparse {
+            self.sparse_sets
+                .insert(TypeId::of::<C>(), Box::new(SparseSet::<C>::new()));
+        }
+
+        // Required components are registered along with the component
+        for component in required_components.iter() {
+            if !self.component_manager.is_registered(component.type_id) {
+                (component.register)(self);
+            }
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the lifecycle [`hooks`](ComponentHooks) of the component type,
+    /// allowing hooks to be registered in addition to the hooks declared
+    /// through the derive macro.
+    ///
+    /// WARNING: Calling this function with a component which is not
+    ///     registered, or setting a hook which is already set, will
+    ///     result in a panic
+    ///
+    pub fn register_component_hooks<C: Component + 'static>(&mut self) -> &mut ComponentHooks {
+        match self.component_manager.get_hooks_mut(TypeId::of::<C>()) {
+            Some(hooks) => hooks,
+            None => panic!("Hook registration failed: Component not registered for use: {}", C::get_name()),
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Makes the registered component type cloneable, letting
+    /// [World::clone_entity] copy the components of the type.
+    ///
+    /// Component types can also be made cloneable through the derive
+    /// macro using the `#[component(clone)]` attribute
+    ///
+    /// WARNING: Calling this function with a component which is not
+    ///     registered will result in a panic
+    ///
+    pub fn register_cloneable<C: Component + Clone + 'static>(&mut self) {
+        if !self
+            .component_manager
+            .set_clone_function(TypeId::of::<C>(), clone_component::<C>)
+        {
+            panic!("Clone registration failed: Component not registered for use: {}", C::get_name());
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Creates an entity with a clone of every cloneable component
+    /// attached to the source entity, and returns its [`id`](Entity).
+    ///
+    /// Components whose type is not cloneable are left out of the clone,
+    /// including the components of the entity hierarchy and the dynamic
+    /// components. Hence the clone is created as a root entity.
+    ///
+    /// The call is ignored (with a warning) if the source entity is not
+    /// alive, in which case [Entity::PLACEHOLDER] is returned
+    ///
+    pub fn clone_entity(&mut self, source: Entity) -> Entity {
+        if !self.check_entity_alive(source, "Entity cloning") {
+            return Entity::PLACEHOLDER;
+        }
+
+        let clone_functions: Vec<_> = self
+            .get_entity_component_infos(source)
+            .into_iter()
+            .filter_map(|info| info.clone_function())
+            .collect();
+
+        let target = self.create_entity();
+        for clone_function in clone_functions {
+            clone_function(self, source, target);
+        }
+        target
+    }
+
+    /// Creates an entity from the [Prefab], along with the entities
+    /// of its child templates, and returns its [`id`](Entity)
+    pub fn instantiate(&mut self, prefab: &Prefab) -> Entity {
+        self.instantiate_with(prefab, |_| {})
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Creates an entity from the [Prefab] like [World::instantiate], then
+    /// calls `overrides` with the new entity, allowing the components of
+    /// the instance to be overridden (or extended)
+    ///
+    pub fn instantiate_with(&mut self, prefab: &Prefab, overrides: impl FnOnce(&mut EntityMut)) -> Entity {
+        let entity_id = self.create_entity();
+        prefab.write(self, entity_id);
+        overrides(&mut EntityMut::new(self, entity_id));
+        entity_id
+    }
+
+    /// Writes the [Prefab] into an existing (e.g. reserved) entity,
+    /// see [World::instantiate]
+    pub(crate) fn instantiate_into(&mut self, prefab: &Prefab, entity_id: Entity) {
+        if !self.check_entity_alive(entity_id, "Prefab instantiation") {
+            return;
+        }
+        prefab.write(self, entity_id);
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Creates an entity with all the components of the [Bundle]
+    /// attached to it, and returns its [`id`](Entity)
+    ///
+    /// WARNING: A bundle containing components which are not registered
+    ///     results in an entity without any components
+    ///
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let entity_id = self.create_entity();
+        self.insert_bundle(entity_id, bundle);
+        entity_id
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Attaches all the components of the [Bundle] to the entity.
+    ///
+    /// The entity is moved into its new archetype only once, regardless
+    /// of the number of components in the bundle.
+    ///
+    /// WARNING: Adding a component type which is already attached to
+    ///     the entity will result in a panic
+    ///
+    pub fn insert_bundle<B: Bundle>(&mut self, entity_id: Entity, bundle: B) {
+        if !self.check_entity_alive(entity_id, "Bundle addition") {
+            return;
+        }
+
+        let types = BundleComponentTypes::of::<B>();
+        if !self.check_bundle_registered(&types) {
+            return;
+        }
+
+        for component in types.with_storage(StorageType::Sparse) {
+            assert!(
+                !self.sparse_sets[&component.type_id].contains(entity_id),
+                "Component addition to Entity [{:?}] failed: Duplicate components are not allowed in entities.",
+                entity_id
+            );
+        }
+
+        let tick = self.read_change_tick();
+        let dense_components: Vec<_> = types.with_storage(StorageType::Dense).collect();
+        let archetype = match self
+            .archetype_manager
+            .add_components(entity_id, &dense_components)
+        {
+            Some(archetype) => archetype,
+            None => return,
+        };
+
+        bundle.write_components(&mut BundleWriter::new(
+            entity_id,
+            tick,
+            archetype,
+            &mut self.sparse_sets,
+        ));
+
+        let component_ids: Vec<TypeId> = types.iter().map(|component| component.type_id).collect();
+        self.insert_required_components(entity_id, &component_ids);
+        self.trigger_hooks(entity_id, &component_ids, |hooks| hooks.on_add);
+        self.trigger_hooks(entity_id, &component_ids, |hooks| hooks.on_insert);
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Removes all the components of the [Bundle] type from the entity.
+    ///
+    /// Components of the bundle which are not attached to the entity
+    /// are ignored.
+    ///
+    pub fn remove_bundle<B: Bundle>(&mut self, entity_id: Entity) {
+        if !self.check_entity_alive(entity_id, "Bundle removal") {
+            return;
+        }
+
+        let types = BundleComponentTypes::of::<B>();
+        if !self.check_bundle_registered(&types) {
+            return;
+        }
 
-        for (_, system) in &mut self.component_managers {
-            // Only remove the component if it is attached to the entity
-            if system.has_component(entity_id) {
-                system.remove_component_from_entity(entity_id);
-            }
+        if let Some(attached_ids) = self.get_entity_component_ids(entity_id) {
+            let removed_ids: Vec<TypeId> = attached_ids
+                .into_iter()
+                .filter(|cid| types.contains(*cid))
+                .collect();
+            self.trigger_hooks(entity_id, &removed_ids, |hooks| hooks.on_remove);
         }
 
-        self.entity_manager.dispose_entity_id(entity_id);
-    }
+        // Only the components attached to the entity are recorded as removed
+        let dense_ids: Vec<TypeId> = types
+            .with_storage(StorageType::Dense)
+            .map(|component| component.type_id)
+            .filter(|cid| self.archetype_manager.has_component(entity_id, *cid))
+            .collect();
 
-    ///### Description
-    ///
-    /// Registers a component type in the manager by creating a
-    /// component systems for handling the component
-    ///
-    /// Components of this type can be attached to generated entities
-    /// only after registering the component type in the [`manager`](World::component_managers).
-    ///
-    pub fn register_component<C: Component + 'static>(&mut self) {
-        if self.check_component_registered::<C>() {
-            println!("Component already registered: {}", C::get_name());
+        if !self.archetype_manager.remove_components(entity_id, &dense_ids) {
+            let err_str = format!("Bundle removal failed: Entity [{:?}] does not exist in the world", entity_id);
+            log::warn!("{err_str}");
             return;
         }
 
-        // Creating system for the component type
-        self.component_managers.insert(
-            Self::get_component_type_id::<C>(),
-            Box::new(ComponentManager::<C>::new()),
-        );
+        let tick = self.read_change_tick();
+        for component_id in dense_ids {
+            self.removed_components.record(component_id, entity_id, tick);
+        }
+
+        for component in types.with_storage(StorageType::Sparse) {
+            let removed = self
+                .sparse_sets
+                .get_mut(&component.type_id)
+                .unwrap()
+                .remove(entity_id);
+
+            if removed {
+                self.removed_components.record(component.type_id, entity_id, tick);
+            }
+        }
     }
 
     /// Adding resource to the world
@@ -234,8 +1136,58 @@ impl World {
             return;
         }
 
-        let system = self.get_manager_mut::<C>();
-        system.add_component_to_entity(entity_id, component);
+        if !self.check_entity_alive(entity_id, "Component addition") {
+            return;
+        }
+
+        let tick = self.read_change_tick();
+        match C::storage_type() {
+            StorageType::Dense => self
+                .archetype_manager
+                .add_component(entity_id, component, tick),
+            StorageType::Sparse => self.get_sparse_set_mut::<C>().insert(entity_id, component, tick),
+        }
+
+        let component_ids = [TypeId::of::<C>()];
+        self.insert_required_components(entity_id, &component_ids);
+        self.trigger_hooks(entity_id, &component_ids, |hooks| hooks.on_add);
+        self.trigger_hooks(entity_id, &component_ids, |hooks| hooks.on_insert);
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Attaches the component to the entity, replacing the component of
+    /// the same type if one is already attached to the entity.
+    ///
+    /// A replaced component is marked as changed, and only triggers the
+    /// `on_insert` [`hook`](ComponentHooks) of the component type.
+    ///
+    pub fn insert_component<C: Component + Sized + 'static>(
+        &mut self,
+        entity_id: Entity,
+        component: C,
+    ) {
+        if !self.check_entity_alive(entity_id, "Component insertion") {
+            return;
+        }
+
+        if !self.has_component::<C>(entity_id) {
+            self.add_component_to_entity(entity_id, component);
+            return;
+        }
+
+        let tick = self.read_change_tick();
+        match C::storage_type() {
+            StorageType::Dense => self
+                .archetype_manager
+                .replace_component(entity_id, component, tick),
+            StorageType::Sparse => self
+                .get_sparse_set_mut::<C>()
+                .replace(entity_id, component, tick),
+        }
+
+        self.trigger_hooks(entity_id, &[TypeId::of::<C>()], |hooks| hooks.on_insert);
     }
 
     ///
@@ -252,14 +1204,241 @@ impl World {
         &mut self,
         entity_id: Entity,
     ) {
+        if !self.check_entity_alive(entity_id, "Component removal") {
+            return;
+        }
+
         if !self.has_component::<C>(entity_id) {
             let err_str = format!("Component Removal failed: Component does not exist in the entity with id {:?}", entity_id);
             log::warn!("{err_str}");
             return;
         }
 
-        let system = self.get_manager_mut::<C>();
-        system.remove_component_from_entity(entity_id);
+        self.remove_typed_component(entity_id, TypeId::of::<C>(), C::storage_type());
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Registers a component type defined at runtime, and returns
+    /// the [ComponentId] identifying the type in the world.
+    ///
+    /// Dynamic components are stored in sparse storages, and their values
+    /// are inserted, read and written as bytes through the component id.
+    /// Dynamic components have neither hooks nor required components, and
+    /// their removals are not reported to
+    /// [`RemovedComponents`](crate::system::param::RemovedComponents).
+    ///
+    pub fn register_dynamic_component(&mut self, descriptor: ComponentDescriptor) -> ComponentId {
+        let storage = DynamicSparseSet::new(descriptor.layout, descriptor.drop);
+        let component_id = self.component_manager.register_dynamic_component(descriptor);
+        self.dynamic_storages.insert(component_id, storage);
+        component_id
+    }
+
+    /// Returns the [ComponentId] of the registered component type [`C`]
+    pub fn component_id<C: Component + 'static>(&self) -> Option<ComponentId> {
+        self.component_manager.get_id(TypeId::of::<C>())
+    }
+
+    /// Returns the [ComponentId] of the first component type
+    /// registered with the given name
+    pub fn component_id_by_name(&self, name: &str) -> Option<ComponentId> {
+        self.component_manager.get_id_by_name(name)
+    }
+
+    pub fn component_info(&self, component_id: ComponentId) -> Option<&ComponentInfo> {
+        self.component_manager.get_info_by_id(component_id)
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Attaches the value of a dynamic component to the entity, replacing
+    /// (and dropping) the value already attached to the entity, if any.
+    ///
+    /// The bytes are moved into the world, hence the world becomes
+    /// responsible for dropping the value through the drop function of
+    /// the [ComponentDescriptor].
+    ///
+    /// The call is ignored (with a warning) if the entity does not exist,
+    /// if the component is not a dynamic component, or if the number of
+    /// bytes does not match the layout of the component
+    ///
+    /// ### Safety
+    ///
+    /// The bytes must be a valid value of the component type described by
+    /// the [ComponentDescriptor] of the component, since the value is
+    /// dropped with the drop function of the descriptor. Plain data
+    /// components without a drop function accept any bytes of the correct
+    /// length.
+    ///
+    pub unsafe fn insert_component_bytes(&mut self, entity_id: Entity, component_id: ComponentId, bytes: &[u8]) {
+        if !self.check_entity_alive(entity_id, "Component addition") {
+            return;
+        }
+
+        let storage = match self.dynamic_storages.get_mut(&component_id) {
+            Some(storage) => storage,
+            None => {
+                log::warn!("Component addition failed: Component [{:?}] is not a dynamic component", component_id);
+                return;
+            }
+        };
+
+        if bytes.len() != storage.layout().size() {
+            log::warn!(
+                "Component addition failed: Expected {} bytes for component [{:?}], found {}",
+                storage.layout().size(),
+                component_id,
+                bytes.len()
+            );
+            return;
+        }
+
+        // @SAFETY: The length of the bytes matches the layout of the component,
+        //      and the caller guarantees the bytes are a valid value
+        unsafe { storage.insert(entity_id, bytes.as_ptr()) };
+    }
+
+    /// Returns the bytes of the dynamic component attached to the entity
+    pub fn get_component_bytes(&self, entity_id: Entity, component_id: ComponentId) -> Option<&[u8]> {
+        if !self.is_alive(entity_id) {
+            return None;
+        }
+
+        let storage = self.dynamic_storages.get(&component_id)?;
+        let ptr = storage.get_ptr(entity_id)?;
+
+        // @SAFETY: The value was initialised from a byte slice of the same length
+        Some(unsafe { std::slice::from_raw_parts(ptr, storage.layout().size()) })
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the mutable bytes of the dynamic component attached to the entity
+    ///
+    /// ### Safety
+    ///
+    /// The bytes written must leave a valid value of the component type,
+    /// see [World::insert_component_bytes]
+    ///
+    pub unsafe fn get_component_bytes_mut(&mut self, entity_id: Entity, component_id: ComponentId) -> Option<&mut [u8]> {
+        if !self.is_alive(entity_id) {
+            return None;
+        }
+
+        let storage = self.dynamic_storages.get(&component_id)?;
+        let ptr = storage.get_ptr(entity_id)?;
+
+        // @SAFETY: The mutable reference to the world guarantees exclusive access
+        Some(unsafe { std::slice::from_raw_parts_mut(ptr, storage.layout().size()) })
+    }
+
+    /// Returns true if the component identified by the [ComponentId]
+    /// is attached to the entity, false otherwise
+    pub fn has_component_by_id(&self, entity_id: Entity, component_id: ComponentId) -> bool {
+        if !self.is_alive(entity_id) {
+            return false;
+        }
+
+        match self.component_manager.get_info_by_id(component_id) {
+            Some(info) => match info.type_id() {
+                Some(type_id) => self.has_component_id(entity_id, type_id),
+                None => self.dynamic_storages[&component_id].contains(entity_id),
+            },
+            None => false,
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Removes the component identified by the [ComponentId] from the
+    /// entity. Works for both statically typed and dynamic components.
+    ///
+    pub fn remove_component_by_id(&mut self, entity_id: Entity, component_id: ComponentId) {
+        if !self.check_entity_alive(entity_id, "Component removal") {
+            return;
+        }
+
+        if !self.has_component_by_id(entity_id, component_id) {
+            let err_str = format!("Component Removal failed: Component does not exist in the entity with id {:?}", entity_id);
+            log::warn!("{err_str}");
+            return;
+        }
+
+        let info = self.component_manager.get_info_by_id(component_id).unwrap();
+        match info.type_id() {
+            Some(type_id) => {
+                let storage_type = info.storage_type();
+                self.remove_typed_component(entity_id, type_id, storage_type);
+            }
+            None => {
+                self.dynamic_storages
+                    .get_mut(&component_id)
+                    .unwrap()
+                    .remove(entity_id);
+            }
+        }
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns the entities which have all the components identified by
+    /// the given [ComponentId]s attached to them, allowing component types
+    /// which are only known at runtime to be matched.
+    ///
+    /// Only the entities are returned: the values of the dynamic components
+    /// of the entities are then read through [World::get_component_bytes].
+    ///
+    /// Unknown component ids (and an empty list of ids) match no entity
+    ///
+    pub fn query_dynamic(&self, component_ids: &[ComponentId]) -> Vec<Entity> {
+        let mut dense_ids = vec![];
+        let mut smallest_storage: Option<&[Entity]> = None;
+
+        for component_id in component_ids {
+            let info = match self.component_manager.get_info_by_id(*component_id) {
+                Some(info) => info,
+                None => return vec![],
+            };
+
+            let entities = match (info.type_id(), info.storage_type()) {
+                (Some(type_id), StorageType::Dense) => {
+                    dense_ids.push(type_id);
+                    continue;
+                }
+                (Some(type_id), StorageType::Sparse) => self.sparse_sets[&type_id].entities(),
+                (None, _) => self.dynamic_storages[component_id].entities(),
+            };
+
+            if smallest_storage.is_none_or(|smallest| entities.len() < smallest.len()) {
+                smallest_storage = Some(entities);
+            }
+        }
+
+        // Candidates are taken from the archetypes when any dense component
+        // is queried, and from the smallest storage otherwise
+        let candidates: Vec<Entity> = if !dense_ids.is_empty() {
+            self.archetype_manager
+                .get_archetypes_with_components(&dense_ids)
+                .flat_map(|archetype| archetype.entities().iter().copied())
+                .collect()
+        } else {
+            smallest_storage.unwrap_or_default().to_vec()
+        };
+
+        candidates
+            .into_iter()
+            .filter(|entity_id| {
+                component_ids
+                    .iter()
+                    .all(|component_id| self.has_component_by_id(*entity_id, *component_id))
+            })
+            .collect()
     }
 
     ///
@@ -280,11 +1459,17 @@ impl World {
             return false;
         }
 
-        // Getting the appropriate system for the component
-        let system = self.get_manager::<C>();
+        if !self.is_alive(entity_id) {
+            return false;
+        }
 
-        // Querying for component presence
-        system.has_component(entity_id)
+        // Querying for component presence in the storage of the component
+        match C::storage_type() {
+            StorageType::Dense => self
+                .archetype_manager
+                .has_component(entity_id, TypeId::of::<C>()),
+            StorageType::Sparse => self.get_sparse_set::<C>().contains(entity_id),
+        }
     }
 
     /// ### Description
@@ -305,6 +1490,9 @@ impl World {
 
     pub fn update_event_state(&mut self) {
         self.event_manager.refresh_update();
+
+        // Component removals follow the buffering rules of events
+        self.removed_components.refresh_update();
     }
 
     ///
@@ -313,6 +1501,22 @@ impl World {
     /// Update the world based on the command buffers received
     pub fn update_world(&mut self) {}
 
+    /// Returns the current change tick of the world
+    pub fn read_change_tick(&self) -> Tick {
+        Tick::new(self.change_tick.load(Ordering::Acquire))
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Advances the change tick of the world and returns the tick
+    /// before the increment, which is assigned to a system run.
+    ///
+    /// Systems running in parallel each get a unique tick
+    pub(crate) fn increment_change_tick(&self) -> Tick {
+        Tick::new(self.change_tick.fetch_add(1, Ordering::AcqRel))
+    }
+
     pub fn set_active(&mut self, active: bool) {
         self.active = active;
     }
@@ -347,61 +1551,10 @@ impl World {
         self.event_manager.get_writer()
     }
 
-    /// 
-    /// ### Description
-    /// 
-    /// Internal API function to get mutable access to the lock 
-    /// on a component of type [`C`] attached to the [`entity_id`](Entity)
-    /// 
-    /// ### Return value:
-    /// This function returns an owned Rw lock write guard, 
-    /// which ensures the validity of data by storing an 
-    /// [Arc] to the data
-    /// 
-    pub(crate) fn get_component_ref_mut_lock<C: Component + 'static>(
-        &self,
-        entity_id: Entity,
-    ) -> Option<OwnedRwLockWriteGuard<C>> {
-        assert!(
-            self.check_component_registered::<C>(),
-            "Component not registered for use {}",
-            C::get_name()
-        );
-
-        let system = self.get_manager::<C>();
-        match system.borrow_component_mut(entity_id) {
-            Some(component_ref) => Some(component_ref),
-            None => None,
-        }
-    }
-
-
-    /// 
-    /// ### Description
-    /// 
-    /// Internal API function to get immutable access to the lock 
-    /// on a component of type [`C`] attached to the [`entity_id`](Entity)
-    /// 
-    /// ### Return value:
-    /// This function returns an owned Rw lock write guard, 
-    /// which ensures the validity of data by storing an 
-    /// [Arc] to the data
-    /// 
-    pub(crate) fn get_component_ref_lock<C: Component + 'static>(
-        &self,
-        entity_id: Entity,
-    ) -> Option<OwnedRwLockReadGuard<C>> {
-        assert!(
-            self.check_component_registered::<C>(),
-            "Component not registered for use {}",
-            C::get_name()
-        );
-
-        let system = self.get_manager::<C>();
-        match system.borrow_component(entity_id) {
-            Some(component_ref) => Some(component_ref),
-            None => None,
-        }
+    /// Returns the record of removals of the component type [`C`], if
+    /// any component of the type has been removed in the last 2 frames
+    pub(crate) fn get_removed_entities<C: Component + 'static>(&self) -> Option<&RemovedEntities> {
+        self.removed_components.get(TypeId::of::<C>())
     }
 
     ///
@@ -459,93 +1612,321 @@ impl World {
     ///
     /// ### Description
     ///
-    /// Returns an array of [`EntityIds`](Entity) which have the
-    /// components specified in the input query parameter type
+    /// Matches the archetypes created since the last update of the
+    /// [QueryState] against the dense components required by the
+    /// query parameter type.
     ///
-    /// ### Parmameters
-    /// - `Query` [SystemQuery] type defining the components to be fetched
-    ///         from the world
+    /// Sparse components are not a part of the archetype tables, hence
+    /// the entities of the matched archetypes must still be checked for
+    /// the sparse components of the query. See [ComponentFetch::contains]
     ///
-    pub(crate) fn get_entities_with_components<QueryType: SystemQuery>(
-        &self,
-    ) -> hashbrown::HashSet<&Entity> {
-        // Getting all active entities in the world
-        // Initially we assume it asks for all entities
-        let mut active_entities = self.entity_manager.get_active_entities();
+    /// ### Parameters
+    /// - `QueryType` [SystemQuery] type defining the components to be fetched
+    ///   from the world
+    ///
+    pub(crate) fn update_query_state<QueryType: SystemQuery>(&self, query_state: &mut QueryState) {
+        let archetypes = self.archetype_manager.archetypes();
+        if query_state.archetype_generation() == archetypes.len() {
+            return;
+        }
 
         // Array of TypeId of Components that the query demands
-        let component_ids = QueryType::get_query_component_ids();
+        let component_ids = QueryType::get_required_component_ids();
 
-        // Finding appropriate component manager for each type
+        let mut dense_component_ids = vec![];
         for cid in component_ids {
-            let component_manager = match self.component_managers.get(&cid) {
-                Some(x) => x,
+            match self.component_manager.get_info(cid) {
+                Some(info) => {
+                    if info.storage_type() == StorageType::Dense {
+                        dense_component_ids.push(cid);
+                    }
+                }
                 None => {
+                    // The state is left as is, since the
+                    // component may be registered later on
                     log::warn!(
-                        "Failed to get manager: TypeId {:?} does not belong to a registered component",
+                        "Failed to get archetypes: TypeId {:?} does not belong to a registered component",
                         cid
                     );
-                    return hashbrown::HashSet::new();
+                    return;
                 }
-            };
+            }
+        }
 
-            // We only keep the intersection of entities with all the previous
-            // component and the entities with the current component.
-            // This ensures that we only shortlist entities which have
-            // all the enlisted components attached to them
-            let component_entities: hashbrown::HashSet<&Entity> =
-                component_manager.get_entities().into_iter().collect();
-            active_entities = component_entities
-                .intersection(&active_entities)
-                .cloned()
-                .collect();
+        // Every archetype which contains all the requested dense component
+        // types stores entities that the query is interested in
+        let new_archetypes = &archetypes[query_state.archetype_generation()..];
+        query_state.extend(
+            new_archetypes
+                .iter()
+                .filter(|archetype| archetype.contains_all(&dense_component_ids))
+                .map(|archetype| archetype.id()),
+            archetypes.len(),
+        );
+    }
+
+    /// Returns the non empty archetypes matched by the [QueryState]
+    pub(crate) fn get_query_archetypes<'a>(&'a self, query_state: &'a QueryState) -> impl Iterator<Item = &'a Archetype> {
+        query_state
+            .matched_archetypes()
+            .iter()
+            .map(|archetype_id| self.archetype_manager.get_archetype(*archetype_id))
+            .filter(|archetype| !archetype.is_empty())
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Resolves the presence of the component type [`C`] in the entities
+    /// of the archetype, without borrowing the storage of the component.
+    ///
+    /// Unregistered component types are absent from every archetype
+    ///
+    pub(crate) fn get_component_presence<C: Component + 'static>(&self, archetype: &Archetype) -> ComponentPresence<'_> {
+        match self.component_manager.get_info(TypeId::of::<C>()).map(|info| info.storage_type()) {
+            Some(StorageType::Dense) if archetype.contains(TypeId::of::<C>()) => ComponentPresence::All,
+            Some(StorageType::Sparse) => ComponentPresence::Sparse(self.sparse_sets[&TypeId::of::<C>()].as_ref()),
+            _ => ComponentPresence::Absent,
+        }
+    }
+
+    /// Returns true if the entities of the archetype are [`disabled`](World::disable)
+    pub(crate) fn archetype_is_disabled(&self, archetype: &Archetype) -> bool {
+        archetype.contains(TypeId::of::<Disabled>())
+    }
+
+    ///
+    /// ### Description
+    ///
+    /// Returns true if entities of the archetype may have a component of
+    /// type [`C`] attached.
+    ///
+    /// Dense components are only present in the archetypes storing them,
+    /// while sparse components may be attached to the entities of any archetype
+    ///
+    pub(crate) fn archetype_may_contain<C: Component + 'static>(&self, archetype: &Archetype) -> bool {
+        match self.component_manager.get_info(TypeId::of::<C>()) {
+            Some(info) => match info.storage_type() {
+                StorageType::Dense => archetype.contains(TypeId::of::<C>()),
+                StorageType::Sparse => true,
+            },
+            None => false,
         }
+    }
 
-        active_entities
+    ///
+    /// ### Description
+    ///
+    /// Resolves the storage of the component type [`C`] for the
+    /// entities of the given archetype.
+    ///
+    /// WARNING: The archetype must store the component type if the
+    ///     component uses the dense storage type, otherwise the function
+    ///     will result in a panic
+    ///
+    pub(crate) fn get_component_fetch<'a, C: Component + 'static>(
+        &'a self,
+        archetype: &'a Archetype,
+    ) -> ComponentFetch<'a, C> {
+        match C::storage_type() {
+            StorageType::Dense => ComponentFetch::Dense(archetype.get_column::<C>().unwrap()),
+            StorageType::Sparse => ComponentFetch::Sparse(self.get_sparse_set::<C>()),
+        }
     }
 
 
-    /// 
+    ///
     /// ### Description
-    /// 
-    /// Internal API methods used by systems to get all read only
-    /// component locks from the manager inside of a [Option<Vector>]
-    /// 
-    /// If any one of the lock acquisition fails, the [`Option`] is 
-    /// returned as a [None] value
-    /// 
-    pub(crate) fn get_all_component_locks<C: Component + 'static>(&self) -> Option<Vec<(Entity, OwnedRwLockReadGuard<C>)>> {
+    ///
+    /// Internal API method used by systems to get immutable raw handles
+    /// into all components of type [`C`] inside of a [Option<Vector>]
+    ///
+    /// The storages of the component are borrowed into the supplied
+    /// `borrows`, which must be kept alive as long as the handles are used.
+    /// If any one of the borrows fails, the [`Option`] is returned as a
+    /// [None] value
+    ///
+    pub(crate) fn get_all_components<C: Component + 'static>(
+        &self,
+        state: &SystemState,
+        borrows: &mut ComponentBorrows,
+    ) -> Option<Vec<ComponentPtr<C>>> {
         assert!(
             self.check_component_registered::<C>(),
             "Component not registered for use: {}",
              C::get_name()
         );
 
-        self.get_manager::<C>().borrow_all_components()
+        let mut vec = vec![];
+        self.visit_component_storages::<C>(|fetch, entities| {
+            if !borrows.try_borrow(fetch.borrow_flag()) {
+                return false;
+            }
+
+            for (row, entity) in entities.iter().enumerate() {
+                vec.push(ComponentPtr::new(
+                    fetch.get_ptr(row, *entity),
+                    fetch.get_ticks_ptr(row, *entity),
+                    *entity,
+                    state.last_run(),
+                    state.this_run(),
+                ));
+            }
+            true
+        })
+        .then_some(vec)
     }
 
 
-    /// 
+    ///
     /// ### Description
-    /// 
-    /// Internal API methods used by systems to get all write access
-    /// component locks from the manager inside of a [Option<Vector>]
-    /// 
-    /// If any one of the lock acquisition fails, the [`Option`] is 
-    /// returned as a [None] value
-    /// 
-    pub(crate) fn get_all_component_locks_mut<C: Component + 'static>(&self) -> Option<Vec<(Entity, OwnedRwLockWriteGuard<C>)>> {
+    ///
+    /// Internal API method used by systems to get mutable raw handles
+    /// into all components of type [`C`] inside of a [Option<Vector>]
+    ///
+    /// The storages of the component are mutably borrowed into the
+    /// supplied `borrows`, which must be kept alive as long as the handles
+    /// are used. If any one of the borrows fails, the [`Option`] is returned
+    /// as a [None] value
+    ///
+    pub(crate) fn get_all_components_mut<C: Component + 'static>(
+        &self,
+        state: &SystemState,
+        borrows: &mut ComponentBorrows,
+    ) -> Option<Vec<MutComponentPtr<C>>> {
         assert!(
             self.check_component_registered::<C>(),
             "Component not registered for use: {}",
              C::get_name()
         );
 
-        self.get_manager::<C>().borrow_all_components_mut()
+        let mut vec = vec![];
+        self.visit_component_storages::<C>(|fetch, entities| {
+            if !borrows.try_borrow_mut(fetch.borrow_flag()) {
+                return false;
+            }
+
+            for (row, entity) in entities.iter().enumerate() {
+                vec.push(MutComponentPtr::new(
+                    fetch.get_ptr(row, *entity),
+                    fetch.get_ticks_ptr(row, *entity),
+                    *entity,
+                    state.last_run(),
+                    state.this_run(),
+                ));
+            }
+            true
+        })
+        .then_some(vec)
     }
 
 
-    pub(crate) fn get_command_writer(&self) -> Sender<Box<dyn FnMut(&mut World) -> ()>> {
+    pub(crate) fn get_command_writer(&self) -> Sender<Box<dyn FnMut(&mut World) + Send>> {
         self.command_sender.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        sync::mpsc::channel,
+    };
+
+    use ecs_macros::Component;
+
+    use super::*;
+    use crate::ecs_base::ECSBase;
+
+    #[derive(Component, Default)]
+    #[component(requires(Second))]
+    struct First;
+
+    #[derive(Component, Default)]
+    #[component(requires(First))]
+    struct Second;
+
+    #[derive(Component, Default)]
+    #[component(requires(Leaf))]
+    struct Root;
+
+    #[derive(Component, Default)]
+    struct Leaf;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Position(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "sparse")]
+    struct Stunned(u32);
+
+    fn new_world() -> World {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Position>();
+        world.register_component::<Stunned>();
+        world
+    }
+
+    #[test]
+    fn components_are_stored_in_their_selected_storage() {
+        let mut world = new_world();
+        let entity_id = world.spawn(Position(1));
+        let archetypes_count = world.archetype_manager.archetypes().len();
+
+        // Sparse components never move the entity across archetypes
+        world.add_component_to_entity(entity_id, Stunned(2));
+        assert_eq!(world.archetype_manager.get_component_ids(entity_id), Some(&[TypeId::of::<Position>()][..]));
+        assert_eq!(world.archetype_manager.archetypes().len(), archetypes_count);
+        assert!(world.sparse_sets[&TypeId::of::<Stunned>()].contains(entity_id));
+
+        assert!(world.has_component::<Stunned>(entity_id));
+        assert_eq!(world.entity(entity_id).get::<Position>(), Some(&Position(1)));
+        assert_eq!(world.entity(entity_id).get::<Stunned>(), Some(&Stunned(2)));
+
+        world.remove_component_from_entity::<Stunned>(entity_id);
+        assert!(!world.has_component::<Stunned>(entity_id));
+        assert!(world.has_component::<Position>(entity_id));
+        assert!(!world.sparse_sets[&TypeId::of::<Stunned>()].contains(entity_id));
+    }
+
+    #[test]
+    fn required_components_are_registered() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+        world.register_component::<Root>();
+
+        assert!(world.check_component_registered::<Root>());
+        assert!(world.check_component_registered::<Leaf>());
+    }
+
+    #[test]
+    fn required_components_cycle_registers_nothing() {
+        let (sender, _receiver) = channel();
+        let mut world = World::new(sender);
+
+        let result = catch_unwind(AssertUnwindSafe(|| world.register_component::<First>()));
+
+        assert!(result.is_err());
+        assert!(!world.check_component_registered::<First>());
+        assert!(!world.check_component_registered::<Second>());
+    }
+
+    #[test]
+    fn operations_on_stale_entities_are_ignored() {
+        let mut world = new_world();
+        let stale = world.spawn(Position(1));
+        world.remove_entity(stale);
+        let reused = world.spawn(Position(2));
+        assert_eq!(reused.index(), stale.index());
+
+        world.add_component_to_entity(stale, Stunned(1));
+        world.remove_component_from_entity::<Position>(stale);
+        world.remove_entity(stale);
+
+        assert!(world.get_entity(stale).is_none());
+        assert!(!world.has_component::<Position>(stale));
+        assert!(!world.has_component::<Stunned>(reused));
+        assert_eq!(world.entity(reused).get::<Position>(), Some(&Position(2)));
+    }
+}
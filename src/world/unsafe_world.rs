@@ -37,7 +37,7 @@ pub struct UnsafeWorldContainer {
 
 impl UnsafeWorldContainer {
     // pub(crate) fn new(command_sender: Sender<CommandFunction>) -> Self {
-    pub(crate) fn new(command_sender: Sender<Box<dyn FnMut(&mut World) -> ()>>) -> Self {
+    pub(crate) fn new(command_sender: Sender<Box<dyn FnMut(&mut World) + Send>>) -> Self {
         Self {
             world: Cell::new(World::new(command_sender)),
         }